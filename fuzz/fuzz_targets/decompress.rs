@@ -0,0 +1,17 @@
+#![no_main]
+use jimage::compression;
+use libfuzzer_sys::fuzz_target;
+
+// Input: u16 strings table length, u32 declared uncompressed size, strings table, resource content
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 6 { return; }
+    let (len, rest) = data.split_at(2);
+    let (size, rest) = rest.split_at(4);
+    let len = (usize::from(u16::from_le_bytes([len[0], len[1]]))).min(rest.len());
+    let size = u64::from(u32::from_le_bytes([size[0], size[1], size[2], size[3]]));
+    let (strings, resource) = rest.split_at(len);
+
+    let _ = compression::decompress(resource, strings, size);
+    let _ = compression::decompress_zip(resource, 1 << 20);
+    let _ = compression::decompress_compact_cp(resource, 1 << 20, strings);
+});
@@ -0,0 +1,11 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(header) = jimage::Header::read(data) {
+        let _ = (header.redirect(), header.offsets(), header.locations(), header.strings(), header.index_size());
+    }
+    if let Ok(file) = jimage::File::from_bytes(data.to_vec()) {
+        let _ = file.header();
+    }
+});
@@ -0,0 +1,15 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let file = match jimage::File::from_bytes(data.to_vec()) { Ok(file) => file, Err(_) => return };
+    let _ = file.verify();
+    for entry in file.resources() {
+        let _ = entry.path();
+        if let Ok(res) = entry.resource(&file) { let _ = res.bytes(); }
+    }
+    file.visit(|r| { let _ = r.resource(); jimage::VisitResult::Continue });
+    let _ = file.find("/java.base/java/lang/Object.class");
+    let _ = file.find_class("java.lang.Object");
+    let _ = file.module_to_packages();
+});
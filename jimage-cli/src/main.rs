@@ -0,0 +1,186 @@
+//! `jimage` - inspect and extract jimage format `lib/modules` files, without a JDK.
+//!
+//! Mirrors the JDK's own `jimage` tool: the same subcommands, options, and (where sensible) output, so scripts
+//! written against one work with the other.  Unlike the JDK's tool, `verify` also checks the image's index, and exits
+//! with a nonzero status if it finds any problems.
+
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+
+// Exit codes, matching the JDK's JImageTask
+const EXIT_OK       : i32 = 0;
+const EXIT_ERROR    : i32 = 1;
+const EXIT_CMDERR   : i32 = 2;
+
+const USAGE : &str = "\
+Usage: jimage <extract | info | list | verify> <options> jimage...
+
+  extract  - Extract all jimage entries and place in a directory specified
+             by the --dir=<directory> (default=.) option.
+
+  info     - Prints detailed information contained in the jimage header.
+
+  list     - Prints the names of all the entries in the jimage.  When used with
+             --verbose, list will also print entry size and offset attributes.
+
+  verify   - Reports on any .class entries that dont verify as classes, and any
+             inconsistencies in the jimage index.
+
+Possible options include:
+          --dir                        Target directory for extract directive
+  -?, -h, --help                       Print this help message
+          --include <pattern-list>     Pattern list for filtering entries.
+          --verbose                    Listing prints entry size and offset
+                                       attributes
+          --version                    Print version information
+
+For options requiring a <pattern-list>, the value will be a comma separated
+list of elements each using one the following forms:
+  <glob-pattern>
+  glob:<glob-pattern>
+";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Task { Extract, Info, List, Verify }
+
+struct Options {
+    task:       Task,
+    dir:        PathBuf,
+    include:    jimage::ExtractFilter,
+    verbose:    bool,
+    jimages:    Vec<PathBuf>,
+}
+
+fn main() {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("Error: {}", message);
+            eprintln!("Usage: jimage <extract | info | list | verify> <options> jimage...");
+            eprintln!("use -h or --help for a list of possible options.");
+            exit(EXIT_CMDERR);
+        },
+    };
+
+    let mut status = EXIT_OK;
+    for path in options.jimages.iter() {
+        let result = jimage::File::open_mmap(path).and_then(|file| match options.task {
+            Task::Extract   => extract(&file, &options.dir, &options.include),
+            Task::Info      => info(&file),
+            Task::List      => list(path, &file, &options.include, options.verbose),
+            Task::Verify    => verify(path, &file),
+        });
+        match result {
+            Ok(true)    => {},
+            Ok(false)   => status = EXIT_ERROR,
+            Err(err)    => { eprintln!("Error: {}", err); status = EXIT_ERROR; },
+        }
+    }
+    exit(status);
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut task = None;
+    let mut dir = PathBuf::from(".");
+    let mut include = jimage::ExtractFilter::new();
+    let mut verbose = false;
+    let mut jimages = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-?" | "-h" | "--help"  => { print!("{}", USAGE); exit(EXIT_OK); },
+            "--version"             => { println!("{}", env!("CARGO_PKG_VERSION")); exit(EXIT_OK); },
+            "--verbose"             => verbose = true,
+            "--dir"                 => dir = args.next().ok_or("no value given for --dir")?.into(),
+            _ if arg.starts_with("--dir=") => dir = arg["--dir=".len()..].into(),
+            "--include"             => add_includes(&mut include, &args.next().ok_or("no value given for --include")?)?,
+            _ if arg.starts_with("--include=") => add_includes(&mut include, &arg["--include=".len()..])?,
+            _ if arg.starts_with('-') => return Err(format!("unknown option: {}", arg)),
+            "extract" if task.is_none() => task = Some(Task::Extract),
+            "info"    if task.is_none() => task = Some(Task::Info),
+            "list"    if task.is_none() => task = Some(Task::List),
+            "verify"  if task.is_none() => task = Some(Task::Verify),
+            _ if task.is_none()     => return Err(format!("unknown task: {}", arg)),
+            _                       => jimages.push(arg.into()),
+        }
+    }
+    let task = task.ok_or("no task given")?;
+    if jimages.is_empty() { return Err("no jimage provided".into()); }
+    Ok(Options { task, dir, include, verbose, jimages })
+}
+
+fn add_includes(include: &mut jimage::ExtractFilter, patterns: &str) -> Result<(), String> {
+    for pattern in patterns.split(',') {
+        if pattern.starts_with("regex:") { return Err(format!("regex patterns aren't supported: {}", pattern)); }
+        let glob = pattern.strip_prefix("glob:").unwrap_or(pattern);
+        include.include(glob).map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+/// Every resource of the file, sorted by module, then path - the order the JDK's tool lists them in
+fn entries(file: &jimage::File) -> jimage::Result<Vec<(jimage::ResourcePath, jimage::Resource<'_>)>> {
+    let mut entries = Vec::new();
+    file.visit(|params| { entries.push(params.to_entry()); jimage::VisitResult::Continue });
+    let mut entries = entries.iter().map(|entry| Ok((entry.path()?, entry.resource(file)?))).collect::<jimage::Result<Vec<_>>>()?;
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(entries)
+}
+
+fn extract(file: &jimage::File, dir: &Path, include: &jimage::ExtractFilter) -> jimage::Result<bool> {
+    file.extract_to(dir, include)?;
+    Ok(true)
+}
+
+fn info(file: &jimage::File) -> jimage::Result<bool> {
+    let header = file.header()?;
+    let row = |name: &str, value: &dyn Display| println!(" {:<16}{}", format!("{}:", name), value);
+    row("Major Version",    &header.major_version);
+    row("Minor Version",    &header.minor_version);
+    row("Flags",            &header.flags);
+    row("Resource Count",   &header.resource_count);
+    row("Table Length",     &header.table_length);
+    row("Offsets Size",     &(header.offsets().end - header.offsets().start));
+    row("Redirects Size",   &(header.redirect().end - header.redirect().start));
+    row("Locations Size",   &header.locations_size);
+    row("Strings Size",     &header.strings_size);
+    row("Index Size",       &header.index_size());
+    Ok(true)
+}
+
+fn list(path: &Path, file: &jimage::File, include: &jimage::ExtractFilter, verbose: bool) -> jimage::Result<bool> {
+    println!("jimage: {}", path.display());
+    let mut module = None;
+    for (path, resource) in entries(file)?.into_iter().filter(|(path, _)| include.matches(path)) {
+        if module.as_deref() != Some(path.module()) {
+            println!();
+            println!("Module: {}", path.module());
+            if verbose { println!("Offset       Size       Compressed Entry"); }
+            module = Some(path.module().to_string());
+        }
+        if verbose {
+            println!("{:>12} {:>10} {:>10} {}", resource.offset().unwrap_or(0), resource.size(), resource.compressed_size().unwrap_or(0), path.path());
+        } else {
+            println!("    {}", path.path());
+        }
+    }
+    Ok(true)
+}
+
+fn verify(path: &Path, file: &jimage::File) -> jimage::Result<bool> {
+    println!("jimage: {}", path.display());
+    let report = file.verify()?;
+    for problem in report.problems.iter() { println!("Error(s) in jimage index: {}", problem); }
+
+    let mut ok = report.is_ok();
+    for (path, resource) in entries(file)? {
+        if path.extension() != "class" { continue; }
+        let mut magic = [0; 4];
+        let is_class = resource.size() >= 4 && resource.get(&mut magic).is_ok() && magic == [0xCA, 0xFE, 0xBA, 0xBE];
+        if !is_class {
+            println!("Error(s) in Class: {}", path);
+            ok = false;
+        }
+    }
+    Ok(ok)
+}
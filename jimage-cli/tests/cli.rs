@@ -0,0 +1,150 @@
+use jimage::write::ImageBuilder;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn tmp(name: &str) -> PathBuf { PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name) }
+
+fn fixture(name: &str) -> PathBuf {
+    let mut b = ImageBuilder::new();
+    b.add("java.base", "java/lang/Object.class", b"\xCA\xFE\xBA\xBE Object".to_vec()).unwrap();
+    b.add("java.base", "module-info.class", b"\xCA\xFE\xBA\xBE module-info".to_vec()).unwrap();
+    let text = b"zipped text, zipped text, zipped text";
+    let zipped = b.zip(text);
+    b.add_compressed("java.base", "META-INF/zipped.txt", zipped, text.len() as u64).unwrap();
+    b.add("java.logging", "java/util/logging/Logger.class", b"\xCA\xFE\xBA\xBE Logger".to_vec()).unwrap();
+    let path = tmp(name);
+    b.write(&path).unwrap();
+    path
+}
+
+fn jimage(args: &[&str], file: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_jimage")).args(args).arg(file).output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(output.status.success(), "{:?}\n{}", output.status, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test] fn list() {
+    let path = fixture("cli_list.jimage");
+    assert_eq!(stdout(&jimage(&["list"], &path)), format!(concat!(
+        "jimage: {}\n",
+        "\n",
+        "Module: java.base\n",
+        "    META-INF/zipped.txt\n",
+        "    java/lang/Object.class\n",
+        "    module-info.class\n",
+        "\n",
+        "Module: java.logging\n",
+        "    java/util/logging/Logger.class\n",
+    ), path.display()));
+
+    let verbose = stdout(&jimage(&["list", "--verbose"], &path));
+    let lines = verbose.lines().collect::<Vec<_>>();
+    assert_eq!(lines[3], "Offset       Size       Compressed Entry");
+    let zipped = lines[4].split_whitespace().collect::<Vec<_>>();
+    assert_eq!((zipped[1], zipped[3]), ("37", "META-INF/zipped.txt"));
+    assert_ne!(zipped[2], "0");
+    assert_eq!(lines[5], "           0         11          0 java/lang/Object.class");
+    assert_eq!(lines[6], "          11         16          0 module-info.class");
+}
+
+#[test] fn include() {
+    let path = fixture("cli_include.jimage");
+    assert_eq!(stdout(&jimage(&["list", "--include", "/java.base/java/**,glob:*/java/util/**"], &path)), format!(concat!(
+        "jimage: {}\n",
+        "\n",
+        "Module: java.base\n",
+        "    java/lang/Object.class\n",
+        "\n",
+        "Module: java.logging\n",
+        "    java/util/logging/Logger.class\n",
+    ), path.display()));
+
+    let dir = tmp("cli_include");
+    let _ = std::fs::remove_dir_all(&dir);
+    stdout(&jimage(&["extract", "--include=*/module-info.class", "--dir", dir.to_str().unwrap()], &path));
+    assert!(dir.join("java.base/module-info.class").exists());
+    assert!(!dir.join("java.base/java").exists());
+
+    assert_eq!(jimage(&["list", "--include", "regex:.*"], &path).status.code(), Some(2));
+}
+
+#[test] fn info() {
+    let path = fixture("cli_info.jimage");
+    let header = jimage::File::open_native(&path).unwrap().header().unwrap();
+    assert_eq!(stdout(&jimage(&["info"], &path)), format!(concat!(
+        " Major Version:  1\n",
+        " Minor Version:  0\n",
+        " Flags:          0\n",
+        " Resource Count: {count}\n",
+        " Table Length:   {length}\n",
+        " Offsets Size:   {tables}\n",
+        " Redirects Size: {tables}\n",
+        " Locations Size: {locations}\n",
+        " Strings Size:   {strings}\n",
+        " Index Size:     {index}\n",
+    ), count = header.resource_count, length = header.table_length, tables = header.table_length * 4, locations = header.locations_size, strings = header.strings_size, index = header.index_size()));
+}
+
+#[test] fn extract() {
+    let path = fixture("cli_extract.jimage");
+    let dir = tmp("cli_extract");
+    let _ = std::fs::remove_dir_all(&dir);
+    stdout(&jimage(&["extract", &format!("--dir={}", dir.display())], &path));
+    assert_eq!(std::fs::read(dir.join("java.base/java/lang/Object.class")).unwrap(), b"\xCA\xFE\xBA\xBE Object");
+    assert_eq!(std::fs::read(dir.join("java.base/META-INF/zipped.txt")).unwrap(), b"zipped text, zipped text, zipped text");
+    assert_eq!(std::fs::read(dir.join("java.logging/java/util/logging/Logger.class")).unwrap(), b"\xCA\xFE\xBA\xBE Logger");
+}
+
+#[test] fn extract_refuses_to_escape() {
+    let mut b = ImageBuilder::new();
+    b.add("java.base", "../../escaped.txt", b"oops".to_vec()).unwrap();
+    let path = tmp("cli_extract_escape.jimage");
+    b.write(&path).unwrap();
+    let dir = tmp("cli_extract_escape/inner");
+    let _ = std::fs::remove_dir_all(&dir);
+    let output = jimage(&["extract", &format!("--dir={}", dir.display())], &path);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("/java.base/../../escaped.txt"));
+    assert!(!dir.join("../escaped.txt").exists() && !dir.join("../../escaped.txt").exists());
+}
+
+#[test] fn verify() {
+    let path = fixture("cli_verify_ok.jimage");
+    assert_eq!(stdout(&jimage(&["verify"], &path)), format!("jimage: {}\n", path.display()));
+
+    let mut b = ImageBuilder::new();
+    b.add("java.base", "java/lang/NotAClass.class", b"text".to_vec()).unwrap();
+    let bogus = b.compress("bogus", b"data", 4);
+    b.add_compressed("java.base", "bogus.txt", bogus, 4).unwrap();
+    let path = tmp("cli_verify_bad.jimage");
+    b.write(&path).unwrap();
+    let output = jimage(&["verify"], &path);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Error(s) in Class: /java.base/java/lang/NotAClass.class\n"), "{}", stdout);
+    assert!(stdout.contains("bogus.txt"), "{}", stdout);
+}
+
+#[test] fn errors() {
+    let output = jimage(&["frobnicate"], Path::new("modules"));
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown task: frobnicate"));
+
+    let output = jimage(&["list"], &tmp("cli_missing.jimage"));
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cli_missing.jimage"));
+}
+
+/// Byte for byte the same output as the JDK's own tool
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn matches_jdk_jimage() {
+    let jdk_jimage = jimage::discover().into_iter().map(|jdk| jdk.home().join("bin").join(if cfg!(windows) { "jimage.exe" } else { "jimage" })).find(|bin| bin.exists())
+        .expect("no local JDK with bin/jimage found - install one, or set JAVA_HOME");
+    let path = fixture("cli_matches_jdk.jimage");
+    for args in [&["info"][..], &["list"], &["list", "--verbose"]].iter() {
+        let jdk = Command::new(&jdk_jimage).args(*args).arg(&path).output().unwrap();
+        assert_eq!(stdout(&jimage(args, &path)), stdout(&jdk), "jimage {:?}", args);
+    }
+}
@@ -0,0 +1,140 @@
+//! A stand-in for `jimage.dll` / `libjimage.so`, exporting the same `JIMAGE_*` entry points as JDK 13+ - plus JDK 9 -
+//! 12's `JIMAGE_ResourcePath` - implemented on top of [jimage::File::open_native].  This lets `jimage::Library` and friends be tested on any machine, without
+//! a JDK of the right version and architecture installed.
+//!
+//! Unlike libjimage, this bounds checks everything and reports precise `JIMAGE_Open` errors - don't rely on either.
+//!
+//! [jimage::File::open_native]:    https://docs.rs/jimage/*/jimage/struct.File.html#method.open_native
+
+#![allow(non_snake_case)]
+#![allow(clippy::missing_safety_doc)] // Same contracts as jimage.hpp
+
+use jimage_sys::*;
+use jni_sys::{jint, jlong};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::ffi::{c_void, CStr};
+use std::os::raw::c_char;
+use std::sync::Mutex;
+
+/// What a `*mut JImageFile` from [JIMAGE_Open] actually points to
+struct Image {
+    file:       jimage::File,
+    locations:  Mutex<Locations>,
+}
+
+/// `JImageLocationRef`s handed out by [JIMAGE_FindResource]: `n` refers to `paths[n-1]`, as `0` means not found
+#[derive(Default)]
+struct Locations {
+    paths:      Vec<String>,
+    refs:       HashMap<String, JImageLocationRef>,
+}
+
+impl Locations {
+    fn intern(&mut self, path: String) -> JImageLocationRef {
+        if let Some(&location) = self.refs.get(&path) { return location; }
+        self.paths.push(path.clone());
+        let location = self.paths.len() as JImageLocationRef;
+        self.refs.insert(path, location);
+        location
+    }
+
+    fn get(&self, location: JImageLocationRef) -> Option<&str> {
+        let index = usize::try_from(location).ok()?.checked_sub(1)?;
+        self.paths.get(index).map(|p| p.as_str())
+    }
+}
+
+unsafe fn image<'i>(image: *mut JImageFile) -> &'i Image { &*(image as *const Image) }
+
+#[no_mangle] pub unsafe extern "C" fn JIMAGE_Open(name: *const c_char, error: *mut jint) -> *mut JImageFile {
+    let path = match CStr::from_ptr(name).to_str() {
+        Ok(path) => path,
+        Err(_) => { *error = JIMAGE_NOT_FOUND; return std::ptr::null_mut(); },
+    };
+    match jimage::File::open_native(path) {
+        Ok(file) => Box::into_raw(Box::new(Image { file, locations: Default::default() })) as *mut JImageFile,
+        Err(err) => {
+            *error = match err {
+                jimage::Error::BadMagic { .. }      => JIMAGE_BAD_MAGIC,
+                jimage::Error::BadVersion { .. }    => JIMAGE_BAD_VERSION,
+                jimage::Error::Corrupted { .. }     => JIMAGE_CORRUPTED,
+                _                                   => JIMAGE_NOT_FOUND,
+            };
+            std::ptr::null_mut()
+        },
+    }
+}
+
+#[no_mangle] pub unsafe extern "C" fn JIMAGE_Close(image: *mut JImageFile) {
+    drop(Box::from_raw(image as *mut Image));
+}
+
+#[no_mangle] pub unsafe extern "C" fn JIMAGE_PackageToModule(image: *mut JImageFile, package_name: *const c_char) -> *const c_char {
+    match self::image(image).file.package_to_module(CStr::from_ptr(package_name)) {
+        Ok(module) => module.as_ptr(), // lives as long as the file does
+        Err(_) => std::ptr::null(),
+    }
+}
+
+#[no_mangle] pub unsafe extern "C" fn JIMAGE_FindResource(image: *mut JImageFile, module_name: *const c_char, version: *const c_char, name: *const c_char, size: *mut jlong) -> JImageLocationRef {
+    let image = self::image(image);
+    let (module_name, name) = (CStr::from_ptr(module_name), CStr::from_ptr(name));
+    match image.file.find_resource(module_name, CStr::from_ptr(version), name) {
+        Ok(resource) => {
+            *size = resource.size() as jlong;
+            let path = format!("/{}/{}", module_name.to_string_lossy(), name.to_string_lossy());
+            image.locations.lock().unwrap().intern(path)
+        },
+        Err(_) => JIMAGE_NOT_FOUND.into(),
+    }
+}
+
+#[no_mangle] pub unsafe extern "C" fn JIMAGE_GetResource(image: *mut JImageFile, location: JImageLocationRef, buffer: *mut c_char, size: jlong) -> jlong {
+    let image = self::image(image);
+    let path = match image.locations.lock().unwrap().get(location) {
+        Some(path) => path.to_string(),
+        None => return JIMAGE_NOT_FOUND.into(),
+    };
+    let buffer : &mut [u8] = match usize::try_from(size) {
+        Ok(size) if !buffer.is_null() => std::slice::from_raw_parts_mut(buffer as *mut u8, size),
+        _ => &mut [],
+    };
+    match image.file.find(&path).and_then(|resource| resource.get(buffer)) {
+        Ok(read) => read as jlong,
+        Err(jimage::Error::NotFound { .. }) => JIMAGE_NOT_FOUND.into(),
+        Err(_) => JIMAGE_CORRUPTED.into(),
+    }
+}
+
+#[no_mangle] pub unsafe extern "C" fn JIMAGE_ResourceIterator(image: *mut JImageFile, visitor: JImageResourceVisitor, arg: *mut c_void) {
+    let file = &self::image(image).file;
+    file.visit(|params| {
+        let more = visitor(image, params.module_name_cstr().as_ptr(), params.version_cstr().as_ptr(), params.package_cstr().as_ptr(), params.name_cstr().as_ptr(), params.extension_cstr().as_ptr(), arg);
+        if more { jimage::VisitResult::Continue } else { jimage::VisitResult::Cancel }
+    });
+}
+
+#[no_mangle] pub unsafe extern "C" fn JIMAGE_ResourcePath(image: *mut JImageFile, location: JImageLocationRef, path: *mut c_char, max: usize) -> bool {
+    let locations = self::image(image).locations.lock().unwrap();
+    match locations.get(location) {
+        Some(name) if name.len() < max => {
+            std::ptr::copy_nonoverlapping(name.as_ptr(), path as *mut u8, name.len());
+            *path.add(name.len()) = 0;
+            true
+        },
+        _ => false,
+    }
+}
+
+/// Fails to compile if the exports above don't exactly match the signatures `jimage_sys` loads them as
+#[allow(dead_code)]
+fn assert_signatures_match(lib: &mut Library) {
+    lib.JIMAGE_Open             = JIMAGE_Open;
+    lib.JIMAGE_Close            = JIMAGE_Close;
+    lib.JIMAGE_PackageToModule  = JIMAGE_PackageToModule;
+    lib.JIMAGE_FindResource     = JIMAGE_FindResource;
+    lib.JIMAGE_GetResource      = JIMAGE_GetResource;
+    lib.JIMAGE_ResourceIterator = JIMAGE_ResourceIterator;
+    lib.JIMAGE_ResourcePath     = Some(JIMAGE_ResourcePath);
+}
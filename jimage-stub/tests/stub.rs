@@ -0,0 +1,155 @@
+// Exercises jimage's Library backend against this crate's stand-in libjimage, so it's tested without a JDK.
+
+#[path = "../../jimage/tests/common/mod.rs"] mod common;
+
+use jimage::Error;
+use jimage_sys::*;
+use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
+use std::ffi::*;
+use std::path::PathBuf;
+use std::ptr::null_mut;
+
+fn cstr(s: &[u8]) -> &CStr { CStr::from_bytes_with_nul(s).unwrap() }
+
+/// The cdylib cargo built alongside this test, in `target/debug/deps` or `target/debug`
+fn stub() -> PathBuf {
+    let name = format!("{}jimage_stub{}", DLL_PREFIX, DLL_SUFFIX);
+    let deps = std::env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    let candidates = [deps.join(&name), deps.parent().unwrap().join(&name)];
+    candidates.iter().find(|path| path.exists()).unwrap_or_else(|| panic!("{} not found next to the test executable", name)).clone()
+}
+
+fn write(name: &str, bytes: &[u8]) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, bytes).unwrap();
+    path
+}
+
+fn fixture() -> common::Fixture {
+    let mut f = common::Fixture::small();
+    let bogus = f.compress("bogus", b"data", 4);
+    f.add_compressed("/java.base/bogus.txt", bogus, 4);
+    f
+}
+
+#[test] fn raw_error_codes() {
+    let lib = jimage_sys::Library::load(&stub()).unwrap();
+    let open = |path: &std::path::Path| {
+        let path = CString::new(path.to_str().unwrap()).unwrap();
+        let mut err = 1;
+        let file = unsafe { (lib.JIMAGE_Open)(path.as_ptr(), &mut err) };
+        (file, err)
+    };
+
+    let mut bad_version = fixture().to_bytes();
+    bad_version[4..8].copy_from_slice(&(2u32 << 16).to_ne_bytes());
+    let mut truncated = fixture().to_bytes();
+    truncated.truncate(64);
+
+    let missing = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("stub_missing.jimage");
+    assert_eq!(open(&missing),                                                                  (null_mut(), JIMAGE_NOT_FOUND));
+    assert_eq!(open(&write("stub_bad_magic.jimage", b"not a jimage file, but long enough to have a header")), (null_mut(), JIMAGE_BAD_MAGIC));
+    assert_eq!(open(&write("stub_bad_version.jimage", &bad_version)),                           (null_mut(), JIMAGE_BAD_VERSION));
+    assert_eq!(open(&write("stub_truncated.jimage", &truncated)),                               (null_mut(), JIMAGE_CORRUPTED));
+
+    let (file, _) = open(&fixture().write("stub_raw.jimage"));
+    assert!(!file.is_null());
+    unsafe {
+        let mut size = -1;
+        assert_eq!((lib.JIMAGE_FindResource)(file, cstr(b"java.base\0").as_ptr(), cstr(b"9.0\0").as_ptr(), cstr(b"java/lang/Missing.class\0").as_ptr(), &mut size), JIMAGE_NOT_FOUND.into());
+        assert_eq!((lib.JIMAGE_PackageToModule)(file, cstr(b"java/nope\0").as_ptr()), std::ptr::null());
+
+        let mut buffer = [0 as std::os::raw::c_char; 4];
+        assert_eq!((lib.JIMAGE_GetResource)(file, 12345, buffer.as_mut_ptr(), 4), JIMAGE_NOT_FOUND.into());
+        let location = (lib.JIMAGE_FindResource)(file, cstr(b"java.base\0").as_ptr(), cstr(b"9.0\0").as_ptr(), cstr(b"bogus.txt\0").as_ptr(), &mut size);
+        assert!(location > 0);
+        assert_eq!(size, 4);
+        assert_eq!((lib.JIMAGE_GetResource)(file, location, buffer.as_mut_ptr(), 4), JIMAGE_CORRUPTED.into());
+
+        let resource_path = lib.JIMAGE_ResourcePath.unwrap();
+        let mut path = [0 as std::os::raw::c_char; 64];
+        assert!(resource_path(file, location, path.as_mut_ptr(), path.len()));
+        assert_eq!(CStr::from_ptr(path.as_ptr()), cstr(b"/java.base/bogus.txt\0"));
+        assert!(!resource_path(file, location, path.as_mut_ptr(), "/java.base/bogus.txt".len()), "no room for the NUL");
+        assert!(!resource_path(file, 12345, path.as_mut_ptr(), path.len()));
+
+        (lib.JIMAGE_Close)(file);
+    }
+}
+
+#[test] fn open_errors() {
+    let lib = jimage::Library::load(stub()).unwrap();
+    assert!(lib.capabilities().resource_path);
+
+    let mut bad_version = fixture().to_bytes();
+    bad_version[4..8].copy_from_slice(&(2u32 << 16 | 3).to_ne_bytes());
+    let err = lib.open(write("stub_open_bad_version.jimage", &bad_version)).err().unwrap();
+    assert!(matches!(err, Error::BadVersion { major: 2, minor: 3, .. }), "{:?}", err);
+    let err = lib.open(write("stub_open_bad_magic.jimage", b"not a jimage file, but long enough to have a header")).err().unwrap();
+    assert!(matches!(err, Error::BadMagic { path: Some(_) }), "{:?}", err);
+    let err = lib.open(PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("stub_open_missing.jimage")).err().unwrap();
+    assert!(matches!(&err, Error::Io { source, .. } if source.kind() == std::io::ErrorKind::NotFound), "{:?}", err);
+}
+
+#[test] fn library_matches_native() {
+    let path = fixture().write("stub_matches_native.jimage");
+    let lib = jimage::Library::load(stub()).unwrap();
+    let file = lib.open(&path).unwrap();
+    let native = jimage::File::open_native(&path).unwrap();
+
+    let mut found_object = false;
+    file.visit(|res| {
+        if res.package_cstr().to_bytes() == b"java/lang" && res.name_cstr().to_bytes() == b"Object" && res.extension_cstr().to_bytes() == b"class" {
+            let res = res.resource().expect("Failed to read java/lang/Object.class");
+            assert_eq!(res.read_to_vec().unwrap(), common::class_bytes("java/lang/Object"));
+            found_object = true;
+        }
+        jimage::VisitResult::Continue
+    });
+    assert!(found_object, "Failed to find java/lang/Object.class");
+
+    let entries = |file: &jimage::File| file.resources().map(|r| r.path().unwrap().to_string()).collect::<Vec<_>>();
+    assert_eq!(entries(&file), entries(&native));
+    let mut visited = Vec::new();
+    file.visit(|r| { visited.push(r.to_entry()); if visited.len() < 3 { jimage::VisitResult::Continue } else { jimage::VisitResult::Cancel } });
+    assert_eq!(visited, file.resources().take(3).collect::<Vec<_>>());
+    let mut resources = file.resources();
+    assert_eq!(resources.size_hint(), (0, None), "nothing enumerated until the first resource is requested");
+    resources.next().unwrap();
+    assert_eq!(resources.size_hint().1, Some(native.resources().count() - 1));
+    for path in entries(&native) {
+        assert_eq!(file.find(&path).unwrap().path().unwrap(), native.find(&path).unwrap().path().unwrap(), "Resource::path via JIMAGE_ResourcePath");
+    }
+    assert_eq!(file.modules().collect::<Vec<_>>(), native.modules().collect::<Vec<_>>());
+
+    let res = file.find_resource(cstr(b"java.logging\0"), cstr(b"9.0\0"), cstr(b"java/util/logging/Logger.class\0")).unwrap();
+    assert_eq!(res.read_to_vec().unwrap(), common::class_bytes("java/util/logging/Logger"));
+    assert_eq!(file.package_to_module(cstr(b"java/util/logging\0")).unwrap(), cstr(b"java.logging\0"));
+
+    match file.find("/java.base/java/lang/Missing.class").err().unwrap() {
+        Error::NotFound { path: Some(p), resource, .. } => { assert_eq!(p, path); assert_eq!(resource, "/java.base/java/lang/Missing.class"); },
+        other => panic!("expected NotFound, got {:?}", other),
+    }
+    assert!(matches!(file.package_to_module(cstr(b"java/nope\0")).err().unwrap(), Error::NotFound { .. }));
+    assert!(matches!(file.find("/java.base/bogus.txt").unwrap().bytes().err().unwrap(), Error::Corrupted { path: Some(_), .. }));
+}
+
+/// What the `local_libjimage_*` tests of jimage check against a real JDK, checked against the stub on every machine
+#[test] fn library_reads() {
+    let mut f = fixture();
+    f.add("/java.base/lines.txt", b"first line\nsecond line\n".to_vec());
+    let zipped = f.zip(b"compressed text, compressed text");
+    f.add_compressed("/java.base/zipped.txt", zipped, 32);
+    let file = jimage::Library::load(stub()).unwrap().open(f.write("stub_reads.jimage")).unwrap();
+
+    let lines = file.find("/java.base/lines.txt").unwrap();
+    let mut small = [0; 5];
+    assert_eq!(lines.get(&mut small).unwrap(), 5);
+    assert_eq!(&small, b"first");
+    assert_eq!(lines.read_to_vec().unwrap(), b"first line\nsecond line\n");
+    assert_eq!(file.find("/java.base/zipped.txt").unwrap().read_to_vec().unwrap(), b"compressed text, compressed text");
+
+    assert_eq!(file.find_class("java.util.Map$Entry").unwrap().read_to_vec().unwrap(), common::class_bytes("java/util/Map$Entry"));
+    assert!(matches!(file.find_class("com.example.Missing").err().unwrap(), Error::UnknownPackage { .. }));
+    assert!(matches!(file.find_class("java.lang.Missing").err().unwrap(), Error::NotFound { .. }));
+}
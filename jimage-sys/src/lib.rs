@@ -68,6 +68,9 @@ pub type JImageLocationRef = jlong;
 pub type JImageResourceVisitor = unsafe extern "C" fn (image: *mut JImageFile, module_name: *const c_char, version: *const c_char, package: *const c_char, name: *const c_char, extension: *const c_char, arg: *mut c_void) -> bool;
 
 /// jimage.dll fns / entry points.  See [jimage.hpp](https://github.com/AdoptOpenJDK/openjdk-jdk13u/blob/f3283b6e2d7676423a23c372754ceef7d2ee731f/src/java.base/share/native/libjimage/jimage.hpp) for more details
+/// 
+/// The non-`Option` entry points are exported by every libjimage from JDK 9 onward, and are required.  `Option` entry
+/// points are `None` when the loaded library doesn't export them.
 pub struct Library {
     // Safety:  These symbols must all exactly match those found in jimage.hpp
     // 
@@ -83,7 +86,13 @@ pub struct Library {
     pub JIMAGE_FindResource:        unsafe extern "C" fn (image: *mut JImageFile, module_name: *const c_char, version: *const c_char, name: *const c_char, size: *mut jlong) -> JImageLocationRef,
     pub JIMAGE_GetResource:         unsafe extern "C" fn (image: *mut JImageFile, location: JImageLocationRef, buffer: *mut c_char, size: jlong) -> jlong,
     pub JIMAGE_ResourceIterator:    unsafe extern "C" fn (image: *mut JImageFile, visitor: JImageResourceVisitor, arg: *mut c_void) -> (),
-    // JIMAGE_ResourcePath was removed: https://github.com/AdoptOpenJDK/openjdk-jdk13u/commit/6b65be6168bcfe398032d33947bcce391f36bba7
+
+    // Optional symbols, not exported by every JDK's libjimage.
+    // 
+    // Definitions of these symbols:    https://github.com/AdoptOpenJDK/openjdk-jdk11u/blob/master/src/java.base/share/native/libjimage/jimage.hpp
+
+    /// JDK 9 - 12 only.  Removed in JDK 13: https://github.com/AdoptOpenJDK/openjdk-jdk13u/commit/6b65be6168bcfe398032d33947bcce391f36bba7
+    pub JIMAGE_ResourcePath:        Option<unsafe extern "C" fn (image: *mut JImageFile, location: JImageLocationRef, path: *mut c_char, max: usize) -> bool>,
 }
 
 impl Library {
@@ -102,6 +111,7 @@ impl Library {
             JIMAGE_FindResource:        lib.sym("JIMAGE_FindResource\0")?,
             JIMAGE_GetResource:         lib.sym("JIMAGE_GetResource\0")?,
             JIMAGE_ResourceIterator:    lib.sym("JIMAGE_ResourceIterator\0")?,
+            JIMAGE_ResourcePath:        lib.sym_opt("JIMAGE_ResourcePath\0"),
         })}
     }
 }
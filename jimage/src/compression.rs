@@ -0,0 +1,237 @@
+//! Decoding of compressed jimage resources, such as those written by `jlink --compress=1` or `--compress=2`.
+//!
+//! A compressed resource's content is a chain of one or more [ResourceHeader]s, each followed by the payload for the
+//! named decompressor.  Decompressing a payload may yield another header, so decompression repeats until the output
+//! no longer starts with [RESOURCE_HEADER_MAGIC].
+//!
+//! Decompressors:
+//! * `"zip"` - zlib-wrapped deflate, see [decompress_zip]
+//! * `"compact-cp"` - class file constant pools with strings shared through the image's strings table, see [decompress_compact_cp]
+//!
+//!  References:
+//! https://github.com/AdoptOpenJDK/openjdk-jdk13u/blob/f3283b6e2d7676423a23c372754ceef7d2ee731f/src/java.base/share/native/libjimage/imageDecompressor.hpp
+//! https://github.com/AdoptOpenJDK/openjdk-jdk13u/blob/f3283b6e2d7676423a23c372754ceef7d2ee731f/src/java.base/share/native/libjimage/imageDecompressor.cpp
+//!
+//! [ResourceHeader]:           struct.ResourceHeader.html
+//! [RESOURCE_HEADER_MAGIC]:    constant.RESOURCE_HEADER_MAGIC.html
+//! [decompress_zip]:           fn.decompress_zip.html
+//! [decompress_compact_cp]:    fn.decompress_compact_cp.html
+
+use crate::{ByteOrder, Error, Result};
+use std::convert::TryFrom;
+use std::ffi::CStr;
+
+/// The magic number (`0xCAFEFAFA`) starting every [ResourceHeader](struct.ResourceHeader.html)
+pub const RESOURCE_HEADER_MAGIC : u32 = 0xCAFE_FAFA;
+
+/// The size of a serialized [ResourceHeader](struct.ResourceHeader.html) in bytes
+pub const RESOURCE_HEADER_LENGTH : usize = 29;
+
+/// The header preceding each layer of a compressed resource
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ResourceHeader {
+    /// [RESOURCE_HEADER_MAGIC](constant.RESOURCE_HEADER_MAGIC.html)
+    pub magic:                      u32,
+    /// The size of the compressed payload following the header
+    pub size:                       u64,
+    /// The size of the payload after decompression
+    pub uncompressed_size:          u64,
+    /// The strings table offset of the decompressor's name (e.g. `"zip"`)
+    pub decompressor_name_offset:   u32,
+    /// The strings table offset of the decompressor's configuration
+    pub decompressor_config_offset: u32,
+    /// If this is the last decompressor applied when the resource was written (and thus the innermost header)
+    pub is_terminal:                bool,
+}
+
+impl ResourceHeader {
+    /// Read a native byte order header from the start of `bytes`, or return `None` if `bytes` doesn't start with a header.
+    pub fn read(bytes: &[u8]) -> Option<Self> {
+        Self::read_with_byte_order(bytes, ByteOrder::NATIVE)
+    }
+
+    /// Read a header written in the image's [ByteOrder](../enum.ByteOrder.html) from the start of `bytes`, or
+    /// return `None` if `bytes` doesn't start with a header.
+    pub fn read_with_byte_order(bytes: &[u8], byte_order: ByteOrder) -> Option<Self> {
+        let bytes = bytes.get(..RESOURCE_HEADER_LENGTH)?;
+        let u4 = |o: usize| byte_order.u4([bytes[o], bytes[o+1], bytes[o+2], bytes[o+3]]);
+        let u8 = |o: usize| byte_order.u8([bytes[o], bytes[o+1], bytes[o+2], bytes[o+3], bytes[o+4], bytes[o+5], bytes[o+6], bytes[o+7]]);
+        let header = Self {
+            magic:                      u4(0),
+            size:                       u8(4),
+            uncompressed_size:          u8(12),
+            decompressor_name_offset:   u4(20),
+            decompressor_config_offset: u4(24),
+            is_terminal:                bytes[28] != 0,
+        };
+        if header.magic == RESOURCE_HEADER_MAGIC { Some(header) } else { None }
+    }
+
+    /// Serialize the header in the image's [ByteOrder](../enum.ByteOrder.html), the inverse of [read_with_byte_order].
+    ///
+    /// [read_with_byte_order]: #method.read_with_byte_order
+    pub fn to_bytes(&self, byte_order: ByteOrder) -> [u8; RESOURCE_HEADER_LENGTH] {
+        let mut bytes = [0; RESOURCE_HEADER_LENGTH];
+        bytes[ 0.. 4].copy_from_slice(&byte_order.u4_bytes(self.magic));
+        bytes[ 4..12].copy_from_slice(&byte_order.u8_bytes(self.size));
+        bytes[12..20].copy_from_slice(&byte_order.u8_bytes(self.uncompressed_size));
+        bytes[20..24].copy_from_slice(&byte_order.u4_bytes(self.decompressor_name_offset));
+        bytes[24..28].copy_from_slice(&byte_order.u4_bytes(self.decompressor_config_offset));
+        bytes[28] = u8::from(self.is_terminal);
+        bytes
+    }
+}
+
+/// Fully decompress a resource's content, unwrapping every [ResourceHeader](struct.ResourceHeader.html) in the chain.
+///
+/// `strings` is the image's strings table, used to look up decompressor names and shared strings.  `uncompressed_size`
+/// is the size the resource's location declares: no layer may decompress to more than this (plus the headers of the
+/// layers within it), and the final result must be exactly this size.
+pub fn decompress(resource: &[u8], strings: &[u8], uncompressed_size: u64) -> Result<Vec<u8>> {
+    decompress_with_byte_order(resource, strings, ByteOrder::NATIVE, uncompressed_size)
+}
+
+/// Like [decompress], for resources of an image with the given [ByteOrder](../enum.ByteOrder.html).
+///
+/// [decompress]:               fn.decompress.html
+pub fn decompress_with_byte_order(resource: &[u8], strings: &[u8], byte_order: ByteOrder, uncompressed_size: u64) -> Result<Vec<u8>> {
+    // jlink applies at most one decompressor per plugin, so anything deeper than this is corrupt (or malicious)
+    const MAX_LAYERS : usize = 16;
+
+    let mut data = resource.to_vec();
+    let mut layers = 0;
+    while let Some(header) = ResourceHeader::read_with_byte_order(&data, byte_order) {
+        layers += 1;
+        if layers > MAX_LAYERS { return Err(invalid(format!("compressed resource has more than {} layers of compression", MAX_LAYERS))); }
+        // the header is as untrusted as the payload, so don't let it claim more than the location does - plus room
+        // for the headers of any layers within it, as outer layers decompress to the next header and its payload
+        let limit = uncompressed_size.saturating_add(((MAX_LAYERS - layers) * RESOURCE_HEADER_LENGTH) as u64);
+        if header.uncompressed_size > limit { return Err(invalid(format!("compressed resource layer claims {} bytes, more than the resource's {}", header.uncompressed_size, uncompressed_size))); }
+        let payload = usize::try_from(header.size).ok()
+            .and_then(|size| data.get(RESOURCE_HEADER_LENGTH..RESOURCE_HEADER_LENGTH.checked_add(size)?))
+            .ok_or_else(|| invalid(format!("compressed resource payload of {} bytes is truncated to {} bytes", header.size, data.len() - RESOURCE_HEADER_LENGTH)))?;
+        let name = string(strings, header.decompressor_name_offset)?;
+        data = match name.to_bytes() {
+            b"zip"          => decompress_zip(payload, header.uncompressed_size)?,
+            b"compact-cp"   => decompress_compact_cp(payload, header.uncompressed_size, strings)?,
+            _               => return Err(invalid(format!("unknown jimage decompressor {:?}", name))),
+        };
+    }
+    if data.len() as u64 != uncompressed_size { return Err(invalid(format!("compressed resource decompressed to {} bytes, expected {}", data.len(), uncompressed_size))); }
+    Ok(data)
+}
+
+/// Decompress a `"zip"` payload: zlib-wrapped deflate data, as written by `java.util.zip.Deflater`.
+pub fn decompress_zip(payload: &[u8], uncompressed_size: u64) -> Result<Vec<u8>> {
+    let limit = usize::try_from(uncompressed_size).map_err(|_| invalid(format!("zip payload too large to decompress: {} bytes", uncompressed_size)))?;
+    let data = miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(payload, limit).map_err(|err| invalid(format!("zip payload failed to inflate: {:?}", err.status)))?;
+    if data.len() as u64 != uncompressed_size { return Err(invalid(format!("zip payload inflated to {} bytes, expected {}", data.len(), uncompressed_size))); }
+    Ok(data)
+}
+
+/// Decompress a `"compact-cp"` payload: a class file whose constant pool UTF8 entries were moved into the image's
+/// strings table (tag 23), with method/field descriptors additionally split into a skeleton and type names (tag 25).
+pub fn decompress_compact_cp(payload: &[u8], uncompressed_size: u64, strings: &[u8]) -> Result<Vec<u8>> {
+    const EXTERNALIZED_STRING               : u8 = 23;
+    const EXTERNALIZED_STRING_DESCRIPTOR    : u8 = 25;
+    const CONSTANT_UTF8                     : u8 = 1;
+    const CONSTANT_LONG                     : u8 = 5;
+    const CONSTANT_DOUBLE                   : u8 = 6;
+    // The payload sizes of all other constant pool entries, by tag
+    const SIZES : [usize; 21] = [0, 0, 0, 4, 4, 8, 8, 2, 2, 4, 4, 4, 4, 0, 0, 3, 2, 4, 4, 2, 2];
+
+    let mut data = Bytes(payload);
+    let mut out = Vec::with_capacity(usize::try_from(uncompressed_size).unwrap_or(0).min(payload.len().saturating_mul(4)));
+    let header = data.take(10)?; // magic, minor, major, constant pool count
+    out.extend_from_slice(header);
+    let cp_count = u16::from_be_bytes([header[8], header[9]]);
+
+    let mut i = 1;
+    while i < cp_count {
+        let tag = data.take(1)?[0];
+        match tag {
+            EXTERNALIZED_STRING => {
+                let s = string(strings, decompress_int(&mut data)?)?.to_bytes();
+                push_utf8(&mut out, s)?;
+            },
+            EXTERNALIZED_STRING_DESCRIPTOR => {
+                // e.g. "(L;I)V" + ["java/lang", "String"] → "(Ljava/lang/String;I)V"
+                let descriptor = string(strings, decompress_int(&mut data)?)?.to_bytes();
+                let indexes_length = decompress_int(&mut data)? as usize;
+                let mut indexes = Bytes(data.take(indexes_length)?);
+                let mut s = Vec::with_capacity(descriptor.len());
+                for &c in descriptor {
+                    s.push(c);
+                    if indexes_length > 0 && c == b'L' {
+                        let package = string(strings, decompress_int(&mut indexes)?)?.to_bytes();
+                        if !package.is_empty() { s.extend_from_slice(package); s.push(b'/'); }
+                        s.extend_from_slice(string(strings, decompress_int(&mut indexes)?)?.to_bytes());
+                    }
+                }
+                push_utf8(&mut out, &s)?;
+            },
+            CONSTANT_UTF8 => {
+                let len = data.take(2)?;
+                let len_n = u16::from_be_bytes([len[0], len[1]]) as usize;
+                out.push(tag);
+                out.extend_from_slice(len);
+                out.extend_from_slice(data.take(len_n)?);
+            },
+            tag if (tag as usize) < SIZES.len() && (SIZES[tag as usize] != 0) => {
+                if tag == CONSTANT_LONG || tag == CONSTANT_DOUBLE { i += 1; } // these take up two constant pool slots
+                out.push(tag);
+                out.extend_from_slice(data.take(SIZES[tag as usize])?);
+            },
+            tag => return Err(invalid(format!("compact-cp payload has unknown constant pool tag {}", tag))),
+        }
+        // externalized strings can expand a few bytes into up to 64 KiB each, so stop as soon as we've overshot
+        if out.len() as u64 > uncompressed_size { return Err(invalid(format!("compact-cp payload decompresses to more than the expected {} bytes", uncompressed_size))); }
+        i += 1;
+    }
+    out.extend_from_slice(data.0); // everything after the constant pool is stored as-is
+
+    if out.len() as u64 != uncompressed_size { return Err(invalid(format!("compact-cp payload decompressed to {} bytes, expected {}", out.len(), uncompressed_size))); }
+    Ok(out)
+}
+
+/// Read a `CompressIndexes`-encoded int: if the high bit of the first byte is set, bits 5-6 are the byte length (1-3)
+/// and the remaining bits are the big-endian value, otherwise it's a plain 4-byte big-endian value.
+fn decompress_int(data: &mut Bytes) -> Result<u32> {
+    let first = data.take(1)?[0];
+    if first & 0x80 != 0 {
+        let len = ((first & 0x60) >> 5) as usize;
+        let rest = data.take(len.saturating_sub(1))?;
+        Ok(rest.iter().fold(u32::from(first & 0x1F), |v, &b| (v << 8) | u32::from(b)))
+    } else {
+        let rest = data.take(3)?;
+        Ok(u32::from_be_bytes([first, rest[0], rest[1], rest[2]]))
+    }
+}
+
+/// A cursor over compact-cp payload bytes
+struct Bytes<'a>(&'a [u8]);
+
+impl<'a> Bytes<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        if n > self.0.len() { return Err(invalid("compact-cp payload is truncated")); }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Ok(head)
+    }
+}
+
+fn push_utf8(out: &mut Vec<u8>, s: &[u8]) -> Result<()> {
+    let len = u16::try_from(s.len()).map_err(|_| invalid(format!("compact-cp string of {} bytes is too long for a class file", s.len())))?;
+    out.push(1);
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(s);
+    Ok(())
+}
+
+fn string(strings: &[u8], offset: u32) -> Result<&CStr> {
+    let tail = strings.get(offset as usize..).ok_or_else(|| invalid(format!("string offset {} is out of bounds", offset)))?;
+    let nul = tail.iter().position(|&b| b == 0).ok_or_else(|| invalid(format!("string at offset {} isn't NUL terminated", offset)))?;
+    Ok(CStr::from_bytes_with_nul(&tail[..=nul]).unwrap())
+}
+
+fn invalid(message: impl Into<String>) -> Error { Error::corrupted(message) }
@@ -0,0 +1,84 @@
+use crate::{Jdk, Library, Result};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Find JDK 9+ installations on this machine, most preferred first
+///
+/// Candidates are checked in order:
+/// * `$JAVA_HOME`
+/// * The `java` found on `$PATH`, with symlinks resolved (e.g. through `/etc/alternatives`)
+/// * SDKMAN (`$SDKMAN_DIR/candidates/java/*`, or `~/.sdkman/...`) and IntelliJ (`~/.jdks/*`) downloads
+/// * System locations: `/usr/lib/jvm/*`, `/usr/java/*`, `/Library/Java/JavaVirtualMachines/*/Contents/Home`,
+///   and `%ProgramFiles%\{Java, AdoptOpenJDK, Eclipse Adoptium, Microsoft, Zulu}\*`
+///
+/// Directories that aren't JDK 9+ installations (e.g. JDK 8 or JRE-only installs) are skipped, as are duplicates.
+pub fn discover() -> Vec<Jdk> {
+    let mut candidates = Vec::new();
+    if let Some(home) = std::env::var_os("JAVA_HOME") { candidates.push(PathBuf::from(home)); }
+    if let Some(home) = java_on_path() { candidates.push(home); }
+
+    let user_home = std::env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" }).map(PathBuf::from);
+    let sdkman = std::env::var_os("SDKMAN_DIR").map(PathBuf::from).or_else(|| user_home.as_ref().map(|h| h.join(".sdkman")));
+    if let Some(sdkman) = sdkman {
+        let java = sdkman.join("candidates").join("java");
+        candidates.push(java.join("current"));
+        candidates.extend(subdirs(&java));
+    }
+    if let Some(user_home) = user_home.as_ref() { candidates.extend(subdirs(&user_home.join(".jdks"))); }
+
+    if cfg!(target_os = "macos") {
+        candidates.extend(subdirs(Path::new("/Library/Java/JavaVirtualMachines")).into_iter().map(|jvm| jvm.join("Contents").join("Home")));
+    } else if cfg!(unix) {
+        candidates.extend(subdirs(Path::new("/usr/lib/jvm")));
+        candidates.extend(subdirs(Path::new("/usr/java")));
+    }
+    if cfg!(windows) {
+        for program_files in ["ProgramW6432", "ProgramFiles"].iter().filter_map(std::env::var_os) {
+            let program_files = PathBuf::from(program_files);
+            for vendor in ["Java", "AdoptOpenJDK", "Eclipse Adoptium", "Microsoft", "Zulu"].iter() {
+                candidates.extend(subdirs(&program_files.join(vendor)));
+            }
+        }
+    }
+
+    let mut seen = BTreeSet::new();
+    candidates.into_iter()
+        .filter_map(Jdk::from_home)
+        .filter(|jdk| seen.insert(jdk.home().canonicalize().unwrap_or_else(|_| jdk.home().to_path_buf())))
+        .collect()
+}
+
+impl Library {
+    /// Load the jimage library of the first JDK [discover] finds that loads successfully
+    ///
+    /// [discover]:     fn.discover.html
+    pub fn find() -> Result<Self> {
+        let mut last_err = None;
+        for jdk in discover() {
+            match jdk.load_library() {
+                Ok(lib) => return Ok(lib),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| crate::Error::not_found(Library::NAME, "no JDK 9+ installation found - try setting JAVA_HOME")))
+    }
+}
+
+/// `$PATH/java` → (resolve symlinks) → `.../bin/java` → `...`
+fn java_on_path() -> Option<PathBuf> {
+    let java = if cfg!(windows) { "java.exe" } else { "java" };
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    let java = std::env::split_paths(&path).map(|dir| dir.join(java)).find(|java| java.is_file())?;
+    let java = java.canonicalize().ok()?;
+    Some(java.parent()?.parent()?.to_path_buf())
+}
+
+/// Subdirectories of `dir`, sorted by name, or none if `dir` can't be read
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = match dir.read_dir() {
+        Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_dir()).collect::<Vec<_>>(),
+        Err(_) => Vec::new(),
+    };
+    dirs.sort();
+    dirs
+}
@@ -0,0 +1,163 @@
+use crate::Header;
+use jimage_sys as sys;
+use jni_sys::jlong;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+/// An error opening or reading a jimage file, or loading a jimage [Library](struct.Library.html)
+///
+/// Errors carry the path of the file in question, and the resource in question where known.  They convert into
+/// [std::io::Error] (preserving [Error::kind]), so existing `std::io::Result` code can keep using `?`.
+///
+/// [std::io::Error]:   https://doc.rust-lang.org/std/io/struct.Error.html
+/// [Error::kind]:      #method.kind
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Reading, mapping, or otherwise accessing a file failed
+    Io { path: Option<PathBuf>, source: io::Error },
+
+    /// Loading a jimage library failed - e.g. it doesn't exist, or is missing `JIMAGE_*` symbols
+    LibraryLoad { path: PathBuf, source: io::Error },
+
+    /// The file doesn't start with the jimage magic number `0xCAFEDADA` (`JIMAGE_BAD_MAGIC`)
+    BadMagic { path: Option<PathBuf> },
+
+    /// The file is a jimage file, but not of the supported 1.0 version (`JIMAGE_BAD_VERSION`)
+    BadVersion { path: Option<PathBuf>, major: u16, minor: u16 },
+
+    /// The file's index, or a resource's content, is inconsistent or undecodable (`JIMAGE_CORRUPTED`)
+    Corrupted { path: Option<PathBuf>, resource: Option<String>, message: String },
+
+    /// No such resource, class, package, or module (`JIMAGE_NOT_FOUND`)
+    NotFound { path: Option<PathBuf>, resource: String, message: String },
+
+    /// [File::find_class](struct.File.html#method.find_class) was asked for a class in a package no module contains
+    UnknownPackage { path: Option<PathBuf>, class: String, package: String },
+
+    /// A module, package, or resource name isn't valid UTF8
+    NonUtf8 { what: &'static str, bytes: Vec<u8> },
+
+    /// An argument was invalid - e.g. an unparseable resource path or class name
+    InvalidInput { message: String },
+
+    /// The loaded jimage library doesn't support the operation - see [Library::capabilities](struct.Library.html#method.capabilities)
+    Unsupported { message: String },
+}
+
+impl Error {
+    /// The closest [std::io::ErrorKind](https://doc.rust-lang.org/std/io/enum.ErrorKind.html) for this error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Io { source, .. }            => source.kind(),
+            Error::LibraryLoad { source, .. }   => source.kind(),
+            Error::BadMagic { .. }              => ErrorKind::InvalidData,
+            Error::BadVersion { .. }            => ErrorKind::InvalidData,
+            Error::Corrupted { .. }             => ErrorKind::InvalidData,
+            Error::NotFound { .. }              => ErrorKind::NotFound,
+            Error::UnknownPackage { .. }        => ErrorKind::NotFound,
+            Error::NonUtf8 { .. }               => ErrorKind::InvalidData,
+            Error::InvalidInput { .. }          => ErrorKind::InvalidInput,
+            Error::Unsupported { .. }           => ErrorKind::Unsupported,
+        }
+    }
+
+    /// The path of the jimage file or library this error relates to, if known
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Error::Io { path, .. } | Error::BadMagic { path } | Error::BadVersion { path, .. } | Error::Corrupted { path, .. } | Error::NotFound { path, .. } | Error::UnknownPackage { path, .. } => path.as_deref(),
+            Error::LibraryLoad { path, .. } => Some(path),
+            Error::NonUtf8 { .. } | Error::InvalidInput { .. } | Error::Unsupported { .. } => None,
+        }
+    }
+
+    pub(crate) fn corrupted(message: impl Into<String>) -> Self { Error::Corrupted { path: None, resource: None, message: message.into() } }
+    pub(crate) fn not_found(resource: impl Into<String>, message: impl Into<String>) -> Self { Error::NotFound { path: None, resource: resource.into(), message: message.into() } }
+    pub(crate) fn invalid_input(message: impl Into<String>) -> Self { Error::InvalidInput { message: message.into() } }
+
+    /// Convert a `JIMAGE_*` error code returned by a jimage [Library](struct.Library.html) for the file at `path`
+    pub(crate) fn from_jimage(code: impl Into<jlong>, resource: impl Display, path: Option<&Path>) -> Self {
+        const JIMAGE_NOT_FOUND      : jlong = sys::JIMAGE_NOT_FOUND as jlong;
+        const JIMAGE_BAD_MAGIC      : jlong = sys::JIMAGE_BAD_MAGIC as jlong;
+        const JIMAGE_BAD_VERSION    : jlong = sys::JIMAGE_BAD_VERSION as jlong;
+        const JIMAGE_CORRUPTED      : jlong = sys::JIMAGE_CORRUPTED as jlong;
+
+        let err = match code.into() {
+            JIMAGE_NOT_FOUND    => Error::not_found(resource.to_string(), "JIMAGE_NOT_FOUND"),
+            JIMAGE_BAD_MAGIC    => Error::BadMagic { path: None },
+            JIMAGE_BAD_VERSION  => {
+                // libjimage never says which version it found, so check ourselves (reporting 0.0 if the file is gone)
+                let header = path.and_then(|path| {
+                    let mut header = [0; Header::SIZE];
+                    std::fs::File::open(path).and_then(|mut f| f.read_exact(&mut header)).ok()?;
+                    Header::read(&header).ok()
+                });
+                let (major, minor) = header.map_or((0, 0), |h| (h.major_version, h.minor_version));
+                Error::BadVersion { path: None, major, minor }
+            },
+            JIMAGE_CORRUPTED    => Error::Corrupted { path: None, resource: Some(resource.to_string()), message: "JIMAGE_CORRUPTED".into() },
+            other               => Error::Corrupted { path: None, resource: Some(resource.to_string()), message: format!("JIMAGE_??? ({})", other) },
+        };
+        err.with_path(path)
+    }
+
+    /// Fill in the path of the jimage file this error relates to, if not already known
+    pub(crate) fn with_path<'p>(mut self, file: impl Into<Option<&'p Path>>) -> Self {
+        let file = match file.into() { Some(file) => file, None => return self };
+        match &mut self {
+            Error::Io { path, .. } | Error::BadMagic { path } | Error::BadVersion { path, .. } | Error::Corrupted { path, .. } | Error::NotFound { path, .. } | Error::UnknownPackage { path, .. } => {
+                if path.is_none() { *path = Some(file.to_path_buf()); }
+            },
+            Error::LibraryLoad { .. } | Error::NonUtf8 { .. } | Error::InvalidInput { .. } | Error::Unsupported { .. } => {},
+        }
+        self
+    }
+
+    /// Fill in the resource this error relates to, if not already known
+    pub(crate) fn with_resource(mut self, name: impl Display) -> Self {
+        if let Error::Corrupted { resource: resource @ None, .. } = &mut self { *resource = Some(name.to_string()); }
+        self
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        if let Some(path) = self.path() { if !matches!(self, Error::LibraryLoad { .. }) { write!(fmt, "{:?}: ", path)?; } }
+        match self {
+            Error::Io { source, .. }                    => write!(fmt, "{}", source),
+            Error::LibraryLoad { path, source }         => write!(fmt, "failed to load jimage library {:?}: {}", path, source),
+            Error::BadMagic { .. }                      => write!(fmt, "not a jimage file (JIMAGE_BAD_MAGIC)"),
+            Error::BadVersion { major, minor, .. }      => write!(fmt, "unsupported jimage version {}.{}, expected 1.0 (JIMAGE_BAD_VERSION)", major, minor),
+            Error::Corrupted { resource: None, message, .. }            => write!(fmt, "corrupted jimage: {}", message),
+            Error::Corrupted { resource: Some(resource), message, .. }  => write!(fmt, "corrupted jimage resource {:?}: {}", resource, message),
+            Error::NotFound { resource, message, .. }   => write!(fmt, "{:?} not found: {}", resource, message),
+            Error::UnknownPackage { class, package, .. } => write!(fmt, "{:?} not found: unknown package {:?}", class, package),
+            Error::NonUtf8 { what, bytes }              => write!(fmt, "{} {:?} isn't valid UTF8", what, String::from_utf8_lossy(bytes)),
+            Error::InvalidInput { message }             => write!(fmt, "{}", message),
+            Error::Unsupported { message }              => write!(fmt, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } | Error::LibraryLoad { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(source: io::Error) -> Self { Error::Io { path: None, source } }
+}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::Io { path: None, source } => source,
+            err => io::Error::new(err.kind(), err),
+        }
+    }
+}
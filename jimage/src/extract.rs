@@ -0,0 +1,138 @@
+use crate::{Error, File, ResourcePath, Result};
+use std::path::{Path, PathBuf};
+
+/// Which resources [File::extract_to] extracts, by module-qualified glob patterns
+///
+/// Patterns are matched against a resource's `module/path` (a leading `/` is optional), one `/`-separated segment at
+/// a time:
+/// * `*` matches any run of characters within a segment, `?` matches any single character within a segment
+/// * `**` as an entire segment matches any number of segments, including none
+///
+/// So `java.base/java/util/**` matches everything under `java.util` and its subpackages, `*/module-info.class`
+/// matches every module's descriptor, and `java.*/**` matches every resource of every `java.*` module.
+///
+/// A resource is extracted if it matches any include (or there are none), and no exclude.
+///
+/// [File::extract_to]:     struct.File.html#method.extract_to
+#[derive(Clone, Debug, Default)]
+pub struct ExtractFilter {
+    includes:   Vec<Glob>,
+    excludes:   Vec<Glob>,
+}
+
+impl ExtractFilter {
+    /// A filter that matches every resource
+    pub fn new() -> Self { Self::default() }
+
+    /// Only extract resources matching `pattern` (or any other include)
+    pub fn include(&mut self, pattern: &str) -> Result<&mut Self> {
+        self.includes.push(Glob::parse(pattern)?);
+        Ok(self)
+    }
+
+    /// Don't extract resources matching `pattern`, even if they match an include
+    pub fn exclude(&mut self, pattern: &str) -> Result<&mut Self> {
+        self.excludes.push(Glob::parse(pattern)?);
+        Ok(self)
+    }
+
+    /// If `path` would be extracted
+    pub fn matches(&self, path: &ResourcePath) -> bool {
+        let segments = std::iter::once(path.module()).chain(path.path().split('/')).collect::<Vec<_>>();
+        (self.includes.is_empty() || self.includes.iter().any(|g| g.matches(&segments))) && !self.excludes.iter().any(|g| g.matches(&segments))
+    }
+}
+
+/// Progress of [File::extract_to_with_progress], reported after each resource is written
+///
+/// [File::extract_to_with_progress]:   struct.File.html#method.extract_to_with_progress
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct ExtractProgress<'a> {
+    /// The resource just written
+    pub resource:   &'a ResourcePath,
+    /// Where it was written to
+    pub path:       &'a Path,
+    /// How many bytes were written
+    pub bytes:      u64,
+    /// How many resources have been written so far, including this one
+    pub done:       usize,
+    /// How many resources matched the filter, and will have been written once extraction is complete
+    pub total:      usize,
+}
+
+/// A parsed include/exclude pattern: one glob per `/`-separated segment
+#[derive(Clone, Debug)]
+struct Glob(Vec<String>);
+
+impl Glob {
+    fn parse(pattern: &str) -> Result<Self> {
+        let trimmed = pattern.strip_prefix('/').unwrap_or(pattern);
+        let segments = trimmed.split('/').map(String::from).collect::<Vec<_>>();
+        if trimmed.is_empty() || segments.iter().any(|s| s.is_empty()) || pattern.contains('\0') {
+            return Err(Error::invalid_input(format!("ExtractFilter glob {:?} is invalid: expected \"module/path\" segments", pattern)));
+        }
+        if segments.iter().any(|s| s.contains("**") && s != "**") {
+            return Err(Error::invalid_input(format!("ExtractFilter glob {:?} is invalid: \"**\" must be an entire segment", pattern)));
+        }
+        Ok(Self(segments))
+    }
+
+    fn matches(&self, path: &[&str]) -> bool { match_segments(&self.0, path) }
+}
+
+fn match_segments(glob: &[String], path: &[&str]) -> bool {
+    match glob.split_first() {
+        None => path.is_empty(),
+        Some((first, rest)) if first == "**" => (0 ..= path.len()).any(|skip| match_segments(rest, &path[skip..])),
+        Some((first, rest)) => match path.split_first() {
+            Some((segment, path)) => match_segment(first.as_bytes(), segment.as_bytes()) && match_segments(rest, path),
+            None => false,
+        },
+    }
+}
+
+fn match_segment(glob: &[u8], name: &[u8]) -> bool {
+    match glob.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0 ..= name.len()).any(|skip| match_segment(rest, &name[skip..])),
+        Some((b'?', rest)) => match std::str::from_utf8(name).ok().and_then(|n| n.chars().next()) {
+            Some(c) => match_segment(rest, &name[c.len_utf8()..]),
+            None => false,
+        },
+        Some((g, rest)) => name.first() == Some(g) && match_segment(rest, &name[1..]),
+    }
+}
+
+pub(crate) fn extract_to(file: &File, dir: &Path, filter: &ExtractFilter, progress: &mut dyn FnMut(ExtractProgress)) -> Result<usize> {
+    let mut resources = Vec::new();
+    for entry in file.resources() {
+        let path = entry.path()?;
+        if filter.matches(&path) { resources.push((path, entry)); }
+    }
+    resources.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let total = resources.len();
+    for (done, (resource, entry)) in resources.iter().enumerate() {
+        let out = destination(dir, resource).ok_or_else(|| Error::corrupted("resource path would escape the extraction directory").with_resource(resource).with_path(file.path()))?;
+        let bytes = entry.resource(file)?.bytes()?;
+        let io = |source| Error::Io { path: Some(out.clone()), source };
+        std::fs::create_dir_all(out.parent().unwrap()).map_err(io)?;
+        std::fs::write(&out, &bytes).map_err(io)?;
+        progress(ExtractProgress { resource, path: &out, bytes: bytes.len() as u64, done: done + 1, total });
+    }
+    Ok(total)
+}
+
+/// `dir/module/package/name.ext`, or `None` if any segment is something like `..` that would escape `dir`
+pub(crate) fn destination(dir: &Path, resource: &ResourcePath) -> Option<PathBuf> {
+    let mut out = dir.to_path_buf();
+    for segment in std::iter::once(resource.module()).chain(resource.path().split('/')) {
+        let mut components = Path::new(segment).components();
+        match (components.next(), components.next()) {
+            (Some(std::path::Component::Normal(_)), None) => out.push(segment),
+            _ => return None,
+        }
+    }
+    Some(out)
+}
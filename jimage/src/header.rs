@@ -0,0 +1,137 @@
+use crate::{Error, Result};
+use crate::image::{HEADER_SIZE, IMAGE_MAGIC};
+use std::ops::Range;
+
+/// The byte order of a jimage file, matching that of the platform it was built for
+///
+/// Headers, tables, `/packages` contents, and compressed [ResourceHeader](compression/struct.ResourceHeader.html)s
+/// are written in this byte order.  Location attributes and compressed payloads are always big-endian.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ByteOrder {
+    LittleEndian,
+    BigEndian,
+}
+
+impl ByteOrder {
+    /// The byte order of the current platform
+    #[cfg(target_endian = "little")]    pub const NATIVE : ByteOrder = ByteOrder::LittleEndian;
+    /// The byte order of the current platform
+    #[cfg(target_endian = "big")]       pub const NATIVE : ByteOrder = ByteOrder::BigEndian;
+
+    pub(crate) fn u4(self, bytes: [u8; 4]) -> u32 {
+        match self {
+            ByteOrder::LittleEndian => u32::from_le_bytes(bytes),
+            ByteOrder::BigEndian    => u32::from_be_bytes(bytes),
+        }
+    }
+
+    pub(crate) fn u8(self, bytes: [u8; 8]) -> u64 {
+        match self {
+            ByteOrder::LittleEndian => u64::from_le_bytes(bytes),
+            ByteOrder::BigEndian    => u64::from_be_bytes(bytes),
+        }
+    }
+
+    pub(crate) fn u4_bytes(self, value: u32) -> [u8; 4] {
+        match self {
+            ByteOrder::LittleEndian => value.to_le_bytes(),
+            ByteOrder::BigEndian    => value.to_be_bytes(),
+        }
+    }
+
+    pub(crate) fn u8_bytes(self, value: u64) -> [u8; 8] {
+        match self {
+            ByteOrder::LittleEndian => value.to_le_bytes(),
+            ByteOrder::BigEndian    => value.to_be_bytes(),
+        }
+    }
+}
+
+/// The header of a jimage file, as returned by [File::header]
+///
+/// The index following the header consists of four sections, in order: the redirect table, the offsets table, the
+/// location attributes, and the strings table.  Resource contents follow the index.  Section ranges are byte offsets
+/// from the start of the file.
+///
+/// [File::header]:     struct.File.html#method.header
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Header {
+    /// `0xCAFEDADA`
+    pub magic:          u32,
+    /// The major version of the jimage format (`1` for all JDKs to date)
+    pub major_version:  u16,
+    /// The minor version of the jimage format (`0` for all JDKs to date)
+    pub minor_version:  u16,
+    /// Currently unused flags
+    pub flags:          u32,
+    /// The number of resources in the file, including `/packages` and `/modules` directory entries
+    pub resource_count: u32,
+    /// The number of slots in the redirect and offsets tables
+    pub table_length:   u32,
+    /// The size of the location attributes section in bytes
+    pub locations_size: u32,
+    /// The size of the strings table in bytes
+    pub strings_size:   u32,
+    /// The byte order of the file, as determined by how the magic number is stored
+    pub byte_order:     ByteOrder,
+}
+
+impl Header {
+    /// The size of a serialized header in bytes
+    pub const SIZE : usize = HEADER_SIZE;
+
+    /// Read a header from the start of a jimage file's bytes.
+    ///
+    /// Any jimage version is accepted, so that unsupported files can still be inspected.
+    pub fn read(bytes: &[u8]) -> Result<Self> {
+        let bytes = bytes.get(..Self::SIZE).ok_or(Error::BadMagic { path: None })?;
+        let magic = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        let byte_order = if u32::from_le_bytes(magic) == IMAGE_MAGIC {
+            ByteOrder::LittleEndian
+        } else if u32::from_be_bytes(magic) == IMAGE_MAGIC {
+            ByteOrder::BigEndian
+        } else {
+            return Err(Error::BadMagic { path: None });
+        };
+        let u4 = |index: usize| byte_order.u4([bytes[index*4], bytes[index*4+1], bytes[index*4+2], bytes[index*4+3]]);
+        Ok(Self {
+            magic:          u4(0),
+            major_version:  (u4(1) >> 16) as u16,
+            minor_version:  u4(1) as u16,
+            flags:          u4(2),
+            resource_count: u4(3),
+            table_length:   u4(4),
+            locations_size: u4(5),
+            strings_size:   u4(6),
+            byte_order,
+        })
+    }
+
+    /// Serialize the header in its [byte_order](#structfield.byte_order), the inverse of [Header::read]
+    ///
+    /// [Header::read]:     #method.read
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        let version = u32::from(self.major_version) << 16 | u32::from(self.minor_version);
+        let values = [self.magic, version, self.flags, self.resource_count, self.table_length, self.locations_size, self.strings_size];
+        for (chunk, &value) in bytes.chunks_exact_mut(4).zip(values.iter()) { chunk.copy_from_slice(&self.byte_order.u4_bytes(value)); }
+        bytes
+    }
+
+    /// The redirect table: `table_length` s4s, used to resolve hash collisions
+    pub fn redirect(&self) -> Range<u64> { let start = Self::SIZE as u64; start .. start + self.table_size() }
+
+    /// The offsets table: `table_length` u4s, each an offset into the location attributes
+    pub fn offsets(&self) -> Range<u64> { let start = self.redirect().end; start .. start + self.table_size() }
+
+    /// The location attributes of every resource
+    pub fn locations(&self) -> Range<u64> { let start = self.offsets().end; start .. start + u64::from(self.locations_size) }
+
+    /// The strings table: NUL terminated (modified) UTF8 names
+    pub fn strings(&self) -> Range<u64> { let start = self.locations().end; start .. start + u64::from(self.strings_size) }
+
+    /// The size of the header and index, and thus the offset at which resource contents start
+    pub fn index_size(&self) -> u64 { self.strings().end }
+
+    fn table_size(&self) -> u64 { u64::from(self.table_length) * 4 }
+}
@@ -0,0 +1,237 @@
+// Pure-Rust reader for jimage-format files, used by File::open_native.
+//
+// This mirrors the layout described by libjimage's C++ sources:
+// https://github.com/AdoptOpenJDK/openjdk-jdk13u/blob/f3283b6e2d7676423a23c372754ceef7d2ee731f/src/java.base/share/native/libjimage/imageFile.hpp
+// https://github.com/AdoptOpenJDK/openjdk-jdk13u/blob/f3283b6e2d7676423a23c372754ceef7d2ee731f/src/java.base/share/native/libjimage/imageFile.cpp
+//
+//  Layout:
+// ┌──────────────────────┐
+// │ header (7 × u4)      │ magic, version, flags, resource count, table length, locations size, strings size
+// │ redirect table       │ table length × s4
+// │ offsets table        │ table length × u4, each an offset into the location attributes
+// │ location attributes  │ locations size bytes
+// │ strings              │ strings size bytes of NUL terminated (modified) UTF8
+// ├──────────────────────┤ "index size"
+// │ resources            │ location offsets are relative to the end of the index
+// └──────────────────────┘
+
+use crate::{compression, Error, Header};
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::ffi::CStr;
+use std::ops::{Deref, Range};
+
+pub(crate) const IMAGE_MAGIC            : u32 = 0xCAFE_DADA;
+pub(crate) const MAJOR_VERSION          : u32 = 1;
+pub(crate) const MINOR_VERSION          : u32 = 0;
+pub(crate) const HEADER_SIZE            : usize = 7 * 4;
+pub(crate) const HASH_MULTIPLIER        : i32 = 0x0100_0193;
+
+pub(crate) const ATTRIBUTE_END          : usize = 0;
+pub(crate) const ATTRIBUTE_MODULE       : usize = 1;
+pub(crate) const ATTRIBUTE_PARENT       : usize = 2;
+pub(crate) const ATTRIBUTE_BASE         : usize = 3;
+pub(crate) const ATTRIBUTE_EXTENSION    : usize = 4;
+pub(crate) const ATTRIBUTE_OFFSET       : usize = 5;
+pub(crate) const ATTRIBUTE_COMPRESSED   : usize = 6;
+pub(crate) const ATTRIBUTE_UNCOMPRESSED : usize = 7;
+pub(crate) const ATTRIBUTE_COUNT        : usize = 8;
+
+/// The version libjimage reports to `JIMAGE_ResourceIterator` visitors, and ignores in `JIMAGE_FindResource`
+pub(crate) const VERSION : &[u8] = b"9\0";
+
+/// The bytes of an entire jimage file
+pub(crate) enum Data {
+    Owned(Vec<u8>),
+    Mapped(memmap2::Mmap),
+}
+
+impl Deref for Data {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        match self {
+            Data::Owned(v)  => v,
+            Data::Mapped(m) => m,
+        }
+    }
+}
+
+/// A parsed, in-memory jimage file
+pub(crate) struct Image {
+    data:           Data,
+    header:         Header,
+    redirect:       Range<usize>,
+    offsets:        Range<usize>,
+    locations:      Range<usize>,
+    strings:        Range<usize>,
+}
+
+impl Image {
+    /// Parse the header and index of a jimage file.
+    pub fn new(data: Data) -> crate::Result<Self> {
+        let header = Header::read(&data)?; // either byte order
+        if u32::from(header.major_version) != MAJOR_VERSION || u32::from(header.minor_version) != MINOR_VERSION {
+            return Err(Error::BadVersion { path: None, major: header.major_version, minor: header.minor_version });
+        }
+
+        let range = |r: Range<u64>| -> crate::Result<Range<usize>> {
+            let overflow = |_| Error::corrupted("index size overflows");
+            Ok(usize::try_from(r.start).map_err(overflow)? .. usize::try_from(r.end).map_err(overflow)?)
+        };
+        let redirect    = range(header.redirect())?;
+        let offsets     = range(header.offsets())?;
+        let locations   = range(header.locations())?;
+        let strings     = range(header.strings())?;
+        if strings.end > data.len() { return Err(Error::corrupted(format!("index of {} bytes is truncated to {} bytes", strings.end, data.len()))); }
+
+        Ok(Self { data, header, redirect, offsets, locations, strings })
+    }
+
+    /// The parsed header.
+    pub fn header(&self) -> &Header { &self.header }
+
+    /// The number of slots in the redirect/offsets tables, and thus the number of locations.
+    pub fn table_length(&self) -> u32 { self.header.table_length }
+
+    /// The offset where resource contents start.
+    pub fn index_size(&self) -> usize { self.strings.end }
+
+    /// Read the `index`th u4 of a table.
+    fn table_u4(&self, table: &Range<usize>, index: u32) -> Option<u32> {
+        let start = table.start.checked_add((index as usize).checked_mul(4)?)?;
+        let end = start.checked_add(4)?;
+        if end > table.end { return None; }
+        let bytes = self.data.get(start..end)?;
+        Some(self.u4(bytes))
+    }
+
+    /// Read a u4 of an image-provided buffer, such as `/packages/...` resource contents.
+    pub fn u4(&self, bytes: &[u8]) -> u32 {
+        self.header.byte_order.u4([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+
+    /// The raw strings table.
+    pub fn strings(&self) -> &[u8] { &self.data[self.strings.clone()] }
+
+    /// Get a NUL terminated string from the strings table.
+    pub fn string(&self, offset: u64) -> Option<&CStr> {
+        let tail = self.strings().get(usize::try_from(offset).ok()?..)?;
+        let nul = tail.iter().position(|&b| b == 0)?;
+        CStr::from_bytes_with_nul(&tail[..=nul]).ok()
+    }
+
+    /// Decode the location stored in the `index`th slot of the offsets table.
+    pub fn location(&self, index: u32) -> Option<Location> {
+        let offset = self.table_u4(&self.offsets, index)? as usize;
+        let attributes = self.data[self.locations.clone()].get(offset..)?;
+        Location::decode(attributes)
+    }
+
+    /// The (module, parent, base, extension) names of the `index`th location, if it's a resource.  Like libjimage's
+    /// `JIMAGE_ResourceIterator`, locations without modules and the "/modules/..." and "/packages/..." directories
+    /// aren't considered resources.
+    pub fn resource_names(&self, index: u32) -> Option<(Location, [&CStr; 4])> {
+        let location = self.location(index)?;
+        let module = self.string(location.get(ATTRIBUTE_MODULE))?;
+        match module.to_bytes() { b"" | b"modules" | b"packages" => return None, _ => {} }
+        Some((location, [
+            module,
+            self.string(location.get(ATTRIBUTE_PARENT))?,
+            self.string(location.get(ATTRIBUTE_BASE))?,
+            self.string(location.get(ATTRIBUTE_EXTENSION))?,
+        ]))
+    }
+
+    /// Find the slot index of a full path such as `b"/java.base/java/lang/Object.class"`.
+    pub fn find_index(&self, path: &[u8]) -> Option<u32> {
+        let length = self.table_length();
+        if length == 0 { return None; }
+        let index = hash_code(path, HASH_MULTIPLIER) as u32 % length;
+        let value = self.table_u4(&self.redirect, index)? as i32;
+        let index = if value > 0 {
+            hash_code(path, value) as u32 % length // collision: the redirect is a seed for a second hash
+        } else if value < 0 {
+            (-1 - value) as u32 // no collision: the redirect is an encoded index
+        } else {
+            return None;
+        };
+        if index < length { Some(index) } else { None }
+    }
+
+    /// Find the location of a full path such as `b"/java.base/java/lang/Object.class"`.
+    pub fn find_location(&self, path: &[u8]) -> Option<Location> {
+        let location = self.location(self.find_index(path)?)?;
+        if self.full_name(&location)? == path { Some(location) } else { None }
+    }
+
+    /// Reconstruct the full path of a location, e.g. `b"/java.base/java/lang/Object.class"`.
+    pub fn full_name(&self, location: &Location) -> Option<Vec<u8>> {
+        let module      = self.string(location.get(ATTRIBUTE_MODULE))?.to_bytes();
+        let parent      = self.string(location.get(ATTRIBUTE_PARENT))?.to_bytes();
+        let base        = self.string(location.get(ATTRIBUTE_BASE))?.to_bytes();
+        let extension   = self.string(location.get(ATTRIBUTE_EXTENSION))?.to_bytes();
+
+        let mut name = Vec::with_capacity(module.len() + parent.len() + base.len() + extension.len() + 4);
+        if !module.is_empty()       { name.push(b'/'); name.extend_from_slice(module); name.push(b'/'); }
+        if !parent.is_empty()       { name.extend_from_slice(parent); name.push(b'/'); }
+        name.extend_from_slice(base);
+        if !extension.is_empty()    { name.push(b'.'); name.extend_from_slice(extension); }
+        Some(name)
+    }
+
+    /// The raw, possibly compressed, bytes of a location's content.
+    pub fn content(&self, location: &Location) -> Option<&[u8]> {
+        let size = match location.get(ATTRIBUTE_COMPRESSED) {
+            0       => location.get(ATTRIBUTE_UNCOMPRESSED),
+            size    => size,
+        };
+        let start = self.index_size().checked_add(usize::try_from(location.get(ATTRIBUTE_OFFSET)).ok()?)?;
+        let end   = start.checked_add(usize::try_from(size).ok()?)?;
+        self.data.get(start..end)
+    }
+
+    /// The uncompressed bytes of a location's content.
+    pub fn resource(&self, location: &Location) -> crate::Result<Cow<'_, [u8]>> {
+        let name = || String::from_utf8_lossy(&self.full_name(location).unwrap_or_default()).into_owned();
+        let content = self.content(location).ok_or_else(|| Error::corrupted("resource content out of bounds").with_resource(name()))?;
+        if location.get(ATTRIBUTE_COMPRESSED) == 0 {
+            Ok(Cow::Borrowed(content))
+        } else {
+            Ok(Cow::Owned(compression::decompress_with_byte_order(content, self.strings(), self.header.byte_order, location.get(ATTRIBUTE_UNCOMPRESSED)).map_err(|err| err.with_resource(name()))?))
+        }
+    }
+}
+
+/// The decoded attributes of a single jimage location
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Location {
+    attributes: [u64; ATTRIBUTE_COUNT],
+}
+
+impl Location {
+    /// Decode a stream of attributes.  Each attribute starts with a byte of `kind << 3 | (length - 1)`, followed
+    /// by `length` big-endian value bytes, until an `ATTRIBUTE_END` kind is encountered.
+    pub fn decode(mut bytes: &[u8]) -> Option<Self> {
+        let mut attributes = [0; ATTRIBUTE_COUNT];
+        loop {
+            let (&byte, rest) = bytes.split_first()?;
+            let kind = (byte >> 3) as usize;
+            if kind == ATTRIBUTE_END { return Some(Self { attributes }); }
+            if kind >= ATTRIBUTE_COUNT { return None; }
+            let length = (byte & 0x7) as usize + 1;
+            let value = rest.get(..length)?;
+            attributes[kind] = value.iter().fold(0, |v, &b| (v << 8) | u64::from(b));
+            bytes = &rest[length..];
+        }
+    }
+
+    /// Get an `ATTRIBUTE_*` value, or 0 if it wasn't present.
+    pub fn get(&self, kind: usize) -> u64 { self.attributes[kind] }
+}
+
+/// `ImageStrings::hash_code`: a FNV-like hash over the bytes of a string, masked to be non-negative.
+pub(crate) fn hash_code(bytes: &[u8], seed: i32) -> i32 {
+    let mut hash = seed;
+    for &b in bytes { hash = hash.wrapping_mul(HASH_MULTIPLIER) ^ i32::from(b); }
+    hash & 0x7FFF_FFFF
+}
@@ -0,0 +1,28 @@
+use crate::ResourceEntry;
+use std::collections::BTreeMap;
+
+/// Modules, their packages, and their resources - gathered in a single pass over [File::resources].
+///
+/// [File::resources]:  struct.File.html#method.resources
+#[derive(Default)]
+pub(crate) struct Index {
+    pub packages:   BTreeMap<String, Vec<String>>,
+    pub resources:  BTreeMap<String, Vec<ResourceEntry>>,
+}
+
+impl Index {
+    pub fn new(entries: impl Iterator<Item = ResourceEntry>) -> Self {
+        let mut index = Self::default();
+        for entry in entries {
+            let module = entry.module_name_cstr().to_string_lossy().into_owned();
+            if !entry.package_cstr().to_bytes().is_empty() {
+                index.packages.entry(module.clone()).or_default().push(entry.package_cstr().to_string_lossy().into_owned());
+            }
+            index.resources.entry(module).or_default().push(entry);
+        }
+        for packages in index.packages.values_mut() { packages.sort(); packages.dedup(); }
+        for resources in index.resources.values_mut() { resources.sort(); }
+        for module in index.resources.keys() { index.packages.entry(module.clone()).or_default(); }
+        index
+    }
+}
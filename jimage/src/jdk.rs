@@ -0,0 +1,150 @@
+use crate::{discover, File, Library, Result};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// A JDK 9+ installation containing a jimage library and a `lib/modules` image, as found by [discover]
+///
+/// Version, vendor, and architecture details come from the JDK's `release` file, if it has one.
+///
+/// ```no_run
+/// // The newest x86_64 JDK 17+ on this machine
+/// let jdk = jimage::Jdk::newest(|jdk| jdk.os_arch() == Some("x86_64") && jdk.version() >= Some(jimage::Version::new(17)));
+/// # let _ = jdk;
+/// ```
+///
+/// [discover]:     fn.discover.html
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Jdk {
+    home:       PathBuf,
+    release:    BTreeMap<String, String>,
+}
+
+impl Jdk {
+    /// Treat `home` (e.g. `$JAVA_HOME`) as a JDK, if it contains both [Jdk::libjimage] and [Jdk::modules]
+    ///
+    /// [Jdk::libjimage]:   #method.libjimage
+    /// [Jdk::modules]:     #method.modules
+    pub fn from_home(home: impl Into<PathBuf>) -> Option<Self> {
+        let home = home.into();
+        let release = std::fs::read_to_string(home.join("release")).map(|r| parse_release(&r)).unwrap_or_default();
+        let jdk = Self { home, release };
+        if jdk.libjimage().is_file() && jdk.modules().is_file() { Some(jdk) } else { None }
+    }
+
+    /// The newest of the [discover]ed JDKs matching `filter`, preferring earlier discoveries when tied
+    ///
+    /// JDKs without a parseable `JAVA_VERSION` are considered older than all others.
+    ///
+    /// [discover]:     fn.discover.html
+    pub fn newest(filter: impl FnMut(&Jdk) -> bool) -> Option<Jdk> {
+        discover().into_iter().filter(filter).min_by_key(|jdk| Reverse(jdk.version()))
+    }
+
+    /// The root directory of the JDK, as `$JAVA_HOME` would be set to
+    pub fn home(&self) -> &Path { &self.home }
+
+    /// The JDK's jimage library - `bin/jimage.dll` on Windows, `lib/libjimage.so` (or `.dylib`) elsewhere
+    pub fn libjimage(&self) -> PathBuf {
+        if cfg!(windows) { self.home.join("bin").join(Library::NAME) } else { self.home.join("lib").join(Library::NAME) }
+    }
+
+    /// The JDK's jimage file, `lib/modules`
+    pub fn modules(&self) -> PathBuf { self.home.join("lib").join("modules") }
+
+    /// Load [Jdk::libjimage]
+    ///
+    /// On Linux and macOS, `libjimage` links against `libjvm` without an rpath to find it by, so the JDK's
+    /// `lib/server/libjvm` is loaded (and never unloaded) first, letting the dynamic loader resolve it by name.
+    ///
+    /// [Jdk::libjimage]:   #method.libjimage
+    pub fn load_library(&self) -> Result<Library> {
+        if cfg!(unix) {
+            let libjvm = self.home.join("lib").join("server").join(if cfg!(target_os = "macos") { "libjvm.dylib" } else { "libjvm.so" });
+            if libjvm.is_file() { let _ = minidl::Library::load(libjvm); }
+        }
+        Library::load(self.libjimage())
+    }
+
+    /// Open [Jdk::modules] with [File::open_mmap], which doesn't need [Jdk::libjimage]
+    ///
+    /// [Jdk::modules]:     #method.modules
+    /// [Jdk::libjimage]:   #method.libjimage
+    /// [File::open_mmap]:  struct.File.html#method.open_mmap
+    pub fn open(&self) -> Result<File> { File::open_mmap(self.modules()) }
+
+    /// A raw value from the `release` file, with quotes removed - e.g. `release("JAVA_VERSION")` → `Some("17.0.2")`
+    pub fn release(&self, key: &str) -> Option<&str> { self.release.get(key).map(|v| v.as_str()) }
+
+    /// `JAVA_VERSION`, e.g. `17.0.2`
+    pub fn version(&self) -> Option<Version> { self.release("JAVA_VERSION")?.parse().ok() }
+
+    /// `IMPLEMENTOR`, e.g. `"Eclipse Adoptium"`, `"Oracle Corporation"`, or `"Debian"`
+    pub fn implementor(&self) -> Option<&str> { self.release("IMPLEMENTOR") }
+
+    /// `OS_ARCH`, e.g. `"x86_64"` or `"aarch64"`
+    pub fn os_arch(&self) -> Option<&str> { self.release("OS_ARCH") }
+
+    /// `MODULES`, the names of the modules linked into [Jdk::modules], e.g. `"java.base"`
+    ///
+    /// [Jdk::modules]:     #method.modules
+    pub fn module_names(&self) -> impl Iterator<Item = &str> { self.release("MODULES").unwrap_or("").split_whitespace() }
+}
+
+/// A Java version number such as `17.0.2`, as found in a [Jdk]'s `release` file
+///
+/// Missing trailing components are zero, while components past the fourth (as in vendor versions like `17.0.4.1.1`)
+/// and any `+build` or `-pre` suffix are ignored.
+///
+/// [Jdk]:  struct.Jdk.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    /// The feature release counter - e.g. `17` for JDK 17
+    pub feature:    u32,
+    /// The interim release counter - `0` for all JDKs to date
+    pub interim:    u32,
+    /// The update release counter - e.g. `2` for JDK 17.0.2
+    pub update:     u32,
+    /// The emergency patch release counter, usually `0`
+    pub patch:      u32,
+}
+
+impl Version {
+    /// `feature.0.0.0`
+    pub const fn new(feature: u32) -> Self { Self { feature, interim: 0, update: 0, patch: 0 } }
+}
+
+impl Display for Version {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(fmt, "{}.{}.{}", self.feature, self.interim, self.update)?;
+        if self.patch != 0 { write!(fmt, ".{}", self.patch)?; }
+        Ok(())
+    }
+}
+
+impl FromStr for Version {
+    type Err = crate::Error;
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || crate::Error::invalid_input(format!("{:?} isn't a Java version number", s));
+        let numbers = s.split(['+', '-']).next().unwrap_or("");
+        let mut parts = [0u32; 4];
+        for (n, part) in numbers.split('.').enumerate() {
+            let part = part.parse().map_err(|_| invalid())?;
+            if let Some(p) = parts.get_mut(n) { *p = part; } // vendors may append more components (17.0.4.1.1), which don't fit
+        }
+        let [feature, interim, update, patch] = parts;
+        Ok(Self { feature, interim, update, patch })
+    }
+}
+
+/// `KEY="value"` lines → `{ KEY: value }`
+fn parse_release(release: &str) -> BTreeMap<String, String> {
+    release.lines().filter_map(|line| {
+        let (key, value) = line.split_at(line.find('=')?);
+        let value = value[1..].trim();
+        let value = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value);
+        Some((key.trim().to_string(), value.to_string()))
+    }).collect()
+}
@@ -0,0 +1,283 @@
+//! Reading and writing `.jmod` files, such as `jdk-13.0.1.9-hotspot/jmods/java.base.jmod`.
+//!
+//! A jmod is a `"JM"` magic number and version, followed by a zip whose entries are grouped into [Section]s by their
+//! first path segment: `classes/`, `conf/`, `lib/`, `bin/`, etc.  [Jmod] exposes every section, and
+//! [File::from_jmod] exposes the `classes/` section through the same [File] / [Resource] / [File::visit] API as
+//! `lib/modules` images.
+//!
+//!  References:
+//! https://github.com/AdoptOpenJDK/openjdk-jdk13u/blob/f3283b6e2d7676423a23c372754ceef7d2ee731f/src/java.base/share/classes/jdk/internal/jmod/JmodFile.java
+//!
+//! [Section]:          enum.Section.html
+//! [Jmod]:             struct.Jmod.html
+//! [File]:             ../struct.File.html
+//! [File::from_jmod]:  ../struct.File.html#method.from_jmod
+//! [File::visit]:      ../struct.File.html#method.visit
+//! [Resource]:         ../struct.Resource.html
+
+use crate::image::Data;
+use crate::zip::{self, ZipEntry, ZipWriter};
+use crate::{Error, File, Result};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// `"JM"`, then the major and minor version (1.0) - the same header `jmod create` writes
+pub(crate) const HEADER : [u8; 4] = [b'J', b'M', 1, 0];
+
+/// A section of a [Jmod](struct.Jmod.html), holding the entries under one top level directory of its zip
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum Section {
+    /// `classes/`: class files and resources, including `module-info.class`
+    Classes,
+    /// `conf/`: configuration files, copied to the runtime image's `conf/`
+    Config,
+    /// `include/`: C headers
+    HeaderFiles,
+    /// `legal/`: license files
+    LegalNotices,
+    /// `man/`: man pages
+    ManPages,
+    /// `lib/`: native libraries
+    NativeLibs,
+    /// `bin/`: native executables
+    NativeCmds,
+}
+
+impl Section {
+    /// Every section, in the order `JmodFile.Section` declares them
+    pub const ALL : [Section; 7] = [Section::Classes, Section::Config, Section::HeaderFiles, Section::LegalNotices, Section::ManPages, Section::NativeLibs, Section::NativeCmds];
+
+    /// The section's directory within the jmod's zip (e.g. `"classes"`)
+    pub fn directory(self) -> &'static str {
+        match self {
+            Section::Classes        => "classes",
+            Section::Config         => "conf",
+            Section::HeaderFiles    => "include",
+            Section::LegalNotices   => "legal",
+            Section::ManPages       => "man",
+            Section::NativeLibs     => "lib",
+            Section::NativeCmds     => "bin",
+        }
+    }
+}
+
+/// An entry of a [Jmod](struct.Jmod.html)
+#[derive(Clone, Debug)]
+pub struct Entry {
+    section:    Section,
+    name:       String,
+    zip:        ZipEntry,
+}
+
+impl Entry {
+    /// The section this entry belongs to
+    pub fn section(&self) -> Section { self.section }
+    /// The path of this entry within its section (e.g. `"java/lang/Object.class"`)
+    pub fn name(&self) -> &str { &self.name }
+    /// How large this entry is in bytes, once decompressed
+    pub fn size(&self) -> u64 { self.zip.size }
+    /// How large this entry's stored content is if compressed, or `0` if it isn't
+    pub fn compressed_size(&self) -> u64 { if self.zip.method == 0 { 0 } else { self.zip.data.len() as u64 } }
+}
+
+/// A parsed `.jmod` file
+pub struct Jmod {
+    path:       Option<PathBuf>, // None if parsed from memory
+    data:       Data,
+    version:    (u8, u8),
+    entries:    Vec<Entry>,
+    module:     CString,
+    resources:  Vec<ClassesEntry>,
+    by_name:    HashMap<String, usize>, // classes/ name -> index into resources
+    packages:   Vec<String>,
+}
+
+/// A `classes/` entry, split into the names [File::visit](../struct.File.html#method.visit) yields
+pub(crate) struct ClassesEntry {
+    pub entry:      usize, // index into Jmod::entries
+    pub package:    CString,
+    pub name:       CString,
+    pub extension:  CString,
+}
+
+impl Jmod {
+    /// Read and parse a `.jmod` file
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).map_err(|err| Error::from(err).with_path(path))?;
+        let mut jmod = Self::parse(Data::Owned(data)).map_err(|err| err.with_path(path))?;
+        jmod.path = Some(path.to_path_buf());
+        Ok(jmod)
+    }
+
+    /// Parse an in-memory `.jmod` file.  Like [File::from_bytes](../struct.File.html#method.from_bytes), everything
+    /// is bounds checked, so this is suitable for untrusted data.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        Self::parse(Data::Owned(bytes))
+    }
+
+    fn parse(data: Data) -> Result<Self> {
+        if data.len() < 4 || data[..2] != HEADER[..2] {
+            return Err(Error::corrupted("not a jmod file: expected \"JM\" magic"));
+        }
+        let version = (data[2], data[3]);
+        if version != (HEADER[2], HEADER[3]) {
+            return Err(Error::corrupted(format!("unsupported jmod version {}.{}, expected 1.0", version.0, version.1)));
+        }
+
+        let mut entries = Vec::new();
+        for zip in zip::read_entries(&data[HEADER.len()..])? {
+            if zip.name.ends_with('/') { continue; } // directories
+            let slash = zip.name.find('/').unwrap_or(zip.name.len());
+            let section = Section::ALL.iter().copied().find(|s| s.directory() == &zip.name[..slash] && slash < zip.name.len())
+                .ok_or_else(|| Error::corrupted(format!("jmod entry {:?} isn't in a known section", zip.name)))?;
+            entries.push(Entry { section, name: zip.name[slash+1..].to_string(), zip });
+        }
+
+        let mut resources = Vec::new();
+        let mut by_name = HashMap::new();
+        let mut packages = Vec::new();
+        for (index, entry) in entries.iter().enumerate().filter(|(_, e)| e.section == Section::Classes) {
+            if entry.name.contains('\0') { return Err(Error::corrupted(format!("jmod entry {:?} contains NUL", entry.name))); }
+            let (package, file) = match entry.name.rfind('/') { Some(i) => (&entry.name[..i], &entry.name[i+1..]), None => ("", &entry.name[..]) };
+            let (name, extension) = match file.rfind('.') { Some(i) => (&file[..i], &file[i+1..]), None => (file, "") };
+            if !package.is_empty() { packages.push(package.to_string()); }
+            by_name.insert(entry.name.clone(), resources.len());
+            resources.push(ClassesEntry {
+                entry:      index,
+                package:    CString::new(package).unwrap(), // checked for NULs above
+                name:       CString::new(name).unwrap(),
+                extension:  CString::new(extension).unwrap(),
+            });
+        }
+        packages.sort();
+        packages.dedup();
+
+        let module_info = by_name.get("module-info.class").map(|&r| &entries[resources[r].entry])
+            .ok_or_else(|| Error::corrupted("jmod has no classes/module-info.class"))?;
+        let module_info = zip::read(&data[HEADER.len()..], &module_info.zip)?;
+        let module = module_name(&module_info).ok_or_else(|| Error::corrupted("couldn't read the module name from module-info.class").with_resource("classes/module-info.class"))?;
+        let module = CString::new(module).map_err(|_| Error::corrupted("module name contains NUL").with_resource("classes/module-info.class"))?;
+
+        Ok(Self { path: None, data, version, entries, module, resources, by_name, packages })
+    }
+
+    /// The path this jmod was opened from, or `None` if it was parsed from memory with [Jmod::from_bytes]
+    ///
+    /// [Jmod::from_bytes]:     #method.from_bytes
+    pub fn path(&self) -> Option<&Path> { self.path.as_deref() }
+
+    /// The major and minor version of the jmod format (currently always `(1, 0)`)
+    pub fn version(&self) -> (u8, u8) { self.version }
+
+    /// The name of the module (e.g. `"java.base"`), as declared by `classes/module-info.class`
+    pub fn module_name(&self) -> &str { self.module.to_str().unwrap_or("") }
+
+    /// The packages ("java/lang", "java/util", ...) of the module's `classes/` section, in sorted order
+    pub fn packages(&self) -> &[String] { &self.packages }
+
+    /// Every entry of every section, in the order they appear in the jmod
+    pub fn entries(&self) -> &[Entry] { &self.entries }
+
+    /// The entries of a single section, in the order they appear in the jmod
+    pub fn section(&self, section: Section) -> impl Iterator<Item = &Entry> + '_ {
+        self.entries.iter().filter(move |e| e.section == section)
+    }
+
+    /// Find an entry by section and name (e.g. `(Section::Config, "logging.properties")`)
+    pub fn find(&self, section: Section, name: &str) -> Result<&Entry> {
+        self.entries.iter().find(|e| e.section == section && e.name == name)
+            .ok_or_else(|| Error::not_found(format!("{}/{}", section.directory(), name), "no such jmod entry").with_path(self.path()))
+    }
+
+    /// Read the contents of an entry.  Stored (uncompressed) entries are borrowed without copying.
+    pub fn read(&self, entry: &Entry) -> Result<Cow<'_, [u8]>> {
+        zip::read(&self.data[HEADER.len()..], &entry.zip).map_err(|err| err.with_path(self.path()))
+    }
+
+    pub(crate) fn module_cstr(&self) -> &CStr { &self.module }
+    pub(crate) fn resources(&self) -> &[ClassesEntry] { &self.resources }
+    pub(crate) fn entry(&self, resource: usize) -> &Entry { &self.entries[self.resources[resource].entry] }
+    pub(crate) fn find_resource(&self, name: &str) -> Option<usize> { self.by_name.get(name).copied() }
+}
+
+/// Read the name of a module from its `module-info.class`: the class's `Module` attribute's `module_name_index`.
+///
+///  References:
+/// https://docs.oracle.com/javase/specs/jvms/se13/html/jvms-4.html#jvms-4.7.25
+pub(crate) fn module_name(class: &[u8]) -> Option<String> {
+    struct Reader<'a>(&'a [u8]);
+    impl<'a> Reader<'a> {
+        fn bytes(&mut self, n: usize) -> Option<&'a [u8]> { if n > self.0.len() { return None; } let (a, b) = self.0.split_at(n); self.0 = b; Some(a) }
+        fn u1(&mut self) -> Option<u8>  { Some(self.bytes(1)?[0]) }
+        fn u2(&mut self) -> Option<u16> { let b = self.bytes(2)?; Some(u16::from_be_bytes([b[0], b[1]])) }
+        fn u4(&mut self) -> Option<u32> { let b = self.bytes(4)?; Some(u32::from_be_bytes([b[0], b[1], b[2], b[3]])) }
+        fn skip_members(&mut self) -> Option<()> {
+            for _ in 0..self.u2()? {
+                self.bytes(6)?; // access_flags, name_index, descriptor_index
+                for _ in 0..self.u2()? { self.u2()?; let n = self.u4()?; self.bytes(n as usize)?; }
+            }
+            Some(())
+        }
+    }
+
+    const UTF8 : u8 = 1;
+    const MODULE : u8 = 19;
+    let mut r = Reader(class);
+    if r.u4()? != 0xCAFE_BABE { return None; }
+    r.bytes(4)?; // minor_version, major_version
+
+    // (tag, utf8 or name_index) per constant pool index
+    let count = r.u2()?;
+    let mut pool = vec![(0, &[][..], 0); usize::from(count)];
+    let mut index = 1;
+    while index < usize::from(count) {
+        let tag = r.u1()?;
+        *pool.get_mut(index)? = match tag {
+            UTF8                => { let n = r.u2()?; (tag, r.bytes(n.into())?, 0) },
+            MODULE | 7 | 8 | 16 | 20 => (tag, &[][..], r.u2()?), // Module, Class, String, MethodType, Package
+            3 | 4 | 9 | 10 | 11 | 12 | 17 | 18 => { r.bytes(4)?; (tag, &[][..], 0) },
+            5 | 6               => { r.bytes(8)?; (tag, &[][..], 0) },
+            15                  => { r.bytes(3)?; (tag, &[][..], 0) }, // MethodHandle
+            _                   => return None,
+        };
+        index += if tag == 5 || tag == 6 { 2 } else { 1 }; // Long & Double take two slots
+    }
+    if index != usize::from(count) { return None; } // a final Long or Double overran the pool
+    let utf8 = |index: u16| match pool.get(usize::from(index)) { Some(&(UTF8, bytes, _)) => std::str::from_utf8(bytes).ok(), _ => None };
+
+    r.bytes(6)?; // access_flags, this_class, super_class
+    let interfaces = r.u2()?;
+    r.bytes(usize::from(interfaces) * 2)?;
+    r.skip_members()?; // fields
+    r.skip_members()?; // methods
+    for _ in 0..r.u2()? {
+        let name = r.u2()?;
+        let len = r.u4()?;
+        let mut info = Reader(r.bytes(len as usize)?);
+        if utf8(name) == Some("Module") {
+            return match pool.get(usize::from(info.u2()?)) {
+                Some(&(MODULE, _, name)) => utf8(name).map(String::from),
+                _ => None,
+            };
+        }
+    }
+    None
+}
+
+pub(crate) fn export(file: &File, module: &str, writer: &mut dyn Write) -> Result<usize> {
+    let resources = file.resources_of(module)?;
+    writer.write_all(&HEADER)?;
+    let mut zip = ZipWriter::new(writer);
+    for entry in resources {
+        let path = entry.path()?;
+        let bytes = entry.resource(file)?.bytes()?;
+        zip.add(&format!("classes/{}", path.path()), &bytes)?;
+    }
+    zip.finish()?;
+    Ok(resources.len())
+}
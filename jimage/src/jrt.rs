@@ -0,0 +1,220 @@
+use crate::{Error, File, Resource, ResourcePath, Result};
+use std::collections::BTreeSet;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// A path within the `jrt:/` file system, such as `/modules/java.base/java/lang/Object.class`
+///
+/// Like Java's `JrtFileSystem`, the root contains two directories:
+/// *   `/modules/<module>/<path>` - every module's resources, and the directories containing them.
+/// *   `/packages/<package>/<module>` - for every package (`java.lang`) including parent packages (`java`), a link to
+///     `/modules/<module>` for each module containing it.  Paths beyond a link (`/packages/java.lang/java.base/java`)
+///     resolve through it.
+///
+/// Paths are normalized on parse: relative paths are resolved against `/`, and `.`, `..`, repeated and trailing `/`s
+/// are removed, so equal paths compare equal.  `jrt:/` URLs name paths under `/modules` - `jrt:/java.base/java/lang`
+/// is `/modules/java.base/java/lang` - and only those can be formatted as URLs again.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct JrtPath {
+    path: String, // normalized: starts with '/', never ends with one unless it's the root
+}
+
+/// What a [JrtPath] resolves to in a [File]
+///
+/// [JrtPath]:  struct.JrtPath.html
+/// [File]:     struct.File.html
+pub enum JrtEntry<'file> {
+    /// A resource, such as `/modules/java.base/java/lang/Object.class`
+    Resource(Resource<'file>),
+
+    /// A directory (or a link to one) and its children, in sorted order.  Children are named relative to the resolved
+    /// path, not its target: `/packages/java.lang/java.base` lists `/packages/java.lang/java.base/java`, etc.
+    Directory(Vec<JrtPath>),
+}
+
+impl JrtPath {
+    /// The root directory, `/`
+    pub fn root() -> Self { Self { path: "/".into() } }
+
+    /// Parse and normalize a path such as `"/modules/java.base/java/lang/Object.class"`, `"/packages/java.lang"`, or
+    /// `"modules/./java.base//java/"`.  Fails only if the path contains a NUL.
+    pub fn parse(path: &str) -> Result<Self> {
+        if path.contains('\0') { return Err(Error::invalid_input(format!("JrtPath::parse({:?}) failed: contains NUL", path))); }
+        let mut segments = Vec::new();
+        for segment in path.split('/') {
+            match segment {
+                "" | "."    => {},
+                ".."        => { segments.pop(); },
+                segment     => segments.push(segment),
+            }
+        }
+        Ok(Self { path: format!("/{}", segments.join("/")) })
+    }
+
+    /// Parse a `jrt:/` URL, such as `"jrt:/java.base/java/lang/Object.class"` (→ `/modules/java.base/java/lang/Object.class`),
+    /// decoding any `%XX` escapes.  Like `JrtFileSystemProvider`, URLs with an authority, query, fragment, or `.` or `..`
+    /// segments are rejected.
+    pub fn from_url(url: &str) -> Result<Self> {
+        let invalid = |reason: &str| Error::invalid_input(format!("JrtPath::from_url({:?}) failed: {}", url, reason));
+        let path = url.get(..4).filter(|scheme| scheme.eq_ignore_ascii_case("jrt:")).map(|_| &url[4..]).ok_or_else(|| invalid("expected a jrt: URL"))?;
+        if !path.starts_with('/') || path.starts_with("//") { return Err(invalid("expected jrt:/<module>/<path>")); }
+        if path.contains(['?', '#']) { return Err(invalid("jrt: URLs can't have a query or fragment")); }
+
+        let mut bytes = Vec::with_capacity(path.len());
+        let mut rest = path.as_bytes();
+        while let Some((&b, after)) = rest.split_first() {
+            if b == b'%' {
+                let hex = after.get(..2).and_then(|h| std::str::from_utf8(h).ok()).and_then(|h| u8::from_str_radix(h, 16).ok()).ok_or_else(|| invalid("invalid % escape"))?;
+                bytes.push(hex);
+                rest = &after[2..];
+            } else {
+                bytes.push(b);
+                rest = after;
+            }
+        }
+        let path = String::from_utf8(bytes).map_err(|_| invalid("% escapes aren't valid UTF-8"))?;
+        if path.split('/').any(|s| s == "." || s == "..") { return Err(invalid("invalid path segment")); }
+        Self::parse(&format!("/modules{}", path))
+    }
+
+    /// Format as a `jrt:/` URL (`/modules/java.base/java/lang` → `"jrt:/java.base/java/lang"`), percent-encoding
+    /// characters URLs can't contain.  Returns `None` for paths outside `/modules`, which have no URL - use
+    /// [JrtPath::real_path] to resolve `/packages` links first.
+    ///
+    /// [JrtPath::real_path]:   #method.real_path
+    pub fn to_url(&self) -> Option<String> {
+        let path = match self.path.strip_prefix("/modules") {
+            Some("")                            => "/",
+            Some(rest) if rest.starts_with('/') => rest,
+            _                                   => return None,
+        };
+        let mut url = String::from("jrt:");
+        for &b in path.as_bytes() {
+            match b {
+                b'A' ..= b'Z' | b'a' ..= b'z' | b'0' ..= b'9' | b'/' | b'-' | b'_' | b'.' | b'!' | b'~' | b'*' | b'\'' | b'(' | b')'
+                | b',' | b';' | b':' | b'$' | b'&' | b'+' | b'=' | b'@' => url.push(char::from(b)),
+                other => url.push_str(&format!("%{:02X}", other)),
+            }
+        }
+        Some(url)
+    }
+
+    /// The normalized path, such as `"/modules/java.base/java/lang/Object.class"`
+    pub fn as_str(&self) -> &str { &self.path }
+
+    /// The module-qualified resource this path would name, if it's beneath a module (`/modules/<module>/<path>`) or
+    /// package link (`/packages/<package>/<module>/<path>`).  This is purely syntactic: the resource may not exist.
+    pub fn resource_path(&self) -> Option<ResourcePath> {
+        let (module, path) = self.module_and_path()?;
+        ResourcePath::new(module, path?).ok()
+    }
+
+    /// Resolve this path against `file`, following `/packages` links.  Fails with [ErrorKind::NotFound] if there's no
+    /// such resource or directory.
+    ///
+    /// Listing the root, `/modules`, or a module's directories enumerates its resources, which is cached after the
+    /// first time, but listing `/packages` or one of its packages enumerates every module's resources each time.
+    ///
+    /// [ErrorKind::NotFound]:  https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.NotFound
+    pub fn resolve<'file>(&self, file: &'file File) -> Result<JrtEntry<'file>> {
+        let not_found = || Error::not_found(self.path.as_str(), "no such file or directory").with_path(file.path());
+        let children = |names: &mut dyn Iterator<Item = String>| -> JrtEntry {
+            JrtEntry::Directory(names.collect::<BTreeSet<_>>().into_iter().map(|name| self.child(&name)).collect())
+        };
+
+        let segments = self.segments();
+        match segments[..] {
+            []                      => Ok(children(&mut ["modules", "packages"].iter().map(|s| s.to_string()))),
+            ["modules"]             => Ok(children(&mut file.modules().map(String::from))),
+            ["packages"]            => Ok(children(&mut packages(file).into_iter().map(|(package, _)| package))),
+            ["packages", package]   => {
+                let modules = packages(file).into_iter().filter(|(p, _)| p == package).map(|(_, module)| module).collect::<Vec<_>>();
+                if modules.is_empty() { return Err(not_found()); }
+                Ok(children(&mut modules.into_iter()))
+            },
+            _ => {
+                let (module, path) = self.module_and_path().ok_or_else(not_found)?;
+                let resources = file.resources_of(module).map_err(|_| not_found())?;
+                if let ["packages", package, ..] = segments[..] {
+                    let package = package.replace('.', "/");
+                    let contains = |p: &String| p.strip_prefix(package.as_str()).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'));
+                    if package.starts_with("META-INF") || !file.packages_of(module)?.iter().any(contains) { return Err(not_found()); }
+                }
+
+                let path = match path {
+                    None        => return Ok(children(&mut resources.iter().filter_map(|r| r.path().ok()).map(|p| first_segment(p.path()).to_string()))),
+                    Some(path)  => path,
+                };
+                if let Some(resource) = ResourcePath::new(module, path).ok().and_then(|p| file.find_path(&p).ok()) {
+                    return Ok(JrtEntry::Resource(resource));
+                }
+                let prefix = format!("{}/", path);
+                let names = resources.iter().filter_map(|r| r.path().ok()).filter_map(|p| p.path().strip_prefix(&prefix).map(|rest| first_segment(rest).to_string())).collect::<Vec<_>>();
+                if names.is_empty() { return Err(not_found()); }
+                Ok(children(&mut names.into_iter()))
+            },
+        }
+    }
+
+    /// Resolve `/packages` links to the `/modules` path they lead to, like `Path::toRealPath`.  Fails with
+    /// [ErrorKind::NotFound] if there's no such resource or directory.
+    ///
+    /// [ErrorKind::NotFound]:  https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.NotFound
+    pub fn real_path(&self, file: &File) -> Result<JrtPath> {
+        let _ = self.resolve(file)?;
+        Ok(match self.module_and_path() {
+            Some((module, None))        => Self { path: format!("/modules/{}", module) },
+            Some((module, Some(path)))  => Self { path: format!("/modules/{}/{}", module, path) },
+            None                        => self.clone(),
+        })
+    }
+
+    fn segments(&self) -> Vec<&str> { self.path.split('/').filter(|s| !s.is_empty()).collect() }
+
+    fn child(&self, name: &str) -> Self {
+        if self.path == "/" { Self { path: format!("/{}", name) } } else { Self { path: format!("{}/{}", self.path, name) } }
+    }
+
+    /// The module, and path within it if any, of `/modules/<module>[/<path>]` or `/packages/<package>/<module>[/<path>]`
+    fn module_and_path(&self) -> Option<(&str, Option<&str>)> {
+        let module_and_path = match self.path.strip_prefix("/modules/") {
+            Some(rest)  => rest,
+            None        => { let rest = self.path.strip_prefix("/packages/")?; &rest[rest.find('/')? + 1 ..] },
+        };
+        Some(match module_and_path.find('/') {
+            Some(slash) => (&module_and_path[..slash], Some(&module_and_path[slash+1..])),
+            None        => (module_and_path, None),
+        })
+    }
+}
+
+impl Display for JrtPath {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result { fmt.write_str(&self.path) }
+}
+
+impl FromStr for JrtPath {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> { Self::parse(s) }
+}
+
+impl From<&ResourcePath> for JrtPath {
+    fn from(path: &ResourcePath) -> Self { Self { path: format!("/modules/{}/{}", path.module(), path.path()) } }
+}
+
+fn first_segment(path: &str) -> &str { path.split('/').next().unwrap_or(path) }
+
+/// Every `(package, module)` pair of `/packages`: each directory containing resources, and its parent directories, as
+/// dotted package names - except for `META-INF`, which jlink doesn't treat as a package.
+fn packages(file: &File) -> BTreeSet<(String, String)> {
+    let mut packages = BTreeSet::new();
+    for module in file.modules() {
+        for package in file.packages_of(module).into_iter().flatten() {
+            if package.starts_with("META-INF") { continue; }
+            let package = package.replace('/', ".");
+            for (i, _) in package.match_indices('.').chain(Some((package.len(), ""))) {
+                packages.insert((package[..i].to_string(), module.to_string()));
+            }
+        }
+    }
+    packages
+}
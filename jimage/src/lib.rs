@@ -2,21 +2,44 @@
 #![cfg_attr(feature = "nightly", doc(include = "../Readme.md"))]
 
 use jimage_sys as sys;
-use jni_sys::jlong;
-use std::fmt::Display;
-use std::ffi::{c_void, CStr};
-use std::io::ErrorKind;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::ffi::{c_void, CStr, CString};
+use std::io::{BufRead, Cursor, ErrorKind, Read, Seek, SeekFrom, Write};
 use std::ops::Drop;
 use std::os::raw::c_char;
-use std::path::Path;
-use std::ptr::{null, null_mut};
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
 
-/// A re-export of [std::io::Error](https://doc.rust-lang.org/std/io/struct.Error.html)
-pub type Error = std::io::Error;
+pub mod compression;
+mod discover;
+mod error;
+mod extract;
+mod header;
+mod image;
+mod index;
+mod jdk;
+pub mod jmod;
+mod jrt;
+mod path;
+mod source;
+mod verify;
+pub mod write;
+mod zip;
 
-/// A re-export of [std::io::Result](https://doc.rust-lang.org/std/io/type.Result.html)
-pub type Result<T> = std::io::Result<T>;
+pub use discover::discover;
+pub use error::Error;
+pub use extract::{ExtractFilter, ExtractProgress};
+pub use header::{ByteOrder, Header};
+pub use jdk::{Jdk, Version};
+pub use jrt::{JrtEntry, JrtPath};
+pub use path::ResourcePath;
+pub use source::{ExplodedModules, Jar, ResourceSource};
+pub use verify::{Problem, VerifyReport};
+
+/// A `Result` with a [jimage::Error](enum.Error.html)
+pub type Result<T> = std::result::Result<T, Error>;
 
 
 
@@ -42,35 +65,108 @@ pub struct Library(Arc<sys::Library>);
 impl Library {
     /// The typical, expected name of the library on this platform - e.g. `"jimage.dll"` or `"libjimage.so"`
     pub const NAME : &'static str = Self::_NAME;
-    #[cfg(windows)] const _NAME : &'static str = "jimage.dll";
-    #[cfg(unix)]    const _NAME : &'static str = "libjimage.so";
+    #[cfg(windows)]                             const _NAME : &'static str = "jimage.dll";
+    #[cfg(all(unix, not(target_os = "macos")))] const _NAME : &'static str = "libjimage.so";
+    #[cfg(target_os = "macos")]                 const _NAME : &'static str = "libjimage.dylib";
 
     /// Load a jimage library such as `jdk-13.0.1.9-hotspot/bin/jimage.dll`
-    pub fn load(path: impl AsRef<Path>) -> Result<Self> { Ok(Self(Arc::new(sys::Library::load(path.as_ref())?))) }
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let lib = sys::Library::load(path).map_err(|source| Error::LibraryLoad { path: path.to_path_buf(), source })?;
+        Ok(Self(Arc::new(lib)))
+    }
 
     /// Open a jimage-format file such as `jdk-13.0.1.9-hotspot/lib/modules`
     pub fn open(&self, path: impl AsRef<Path>) -> Result<File> { File::open(self, path) }
+
+    /// Which optional `JIMAGE_*` entry points this library exports
+    pub fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            resource_path: self.0.JIMAGE_ResourcePath.is_some(),
+        }
+    }
+}
+
+/// Optional functionality of a jimage [Library], which varies by JDK version.  See [Library::capabilities].
+/// 
+/// [Library]:              struct.Library.html
+/// [Library::capabilities]: struct.Library.html#method.capabilities
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// `JIMAGE_ResourcePath` (JDK 9 - 12): [Resource::path] works for resources of this library's files.
+    /// Natively parsed files always support [Resource::path].
+    /// 
+    /// [Resource::path]:   struct.Resource.html#method.path
+    pub resource_path: bool,
 }
 
 /// A loaded jimage file such as `jdk-13.0.1.9-hotspot/lib/modules`
+/// 
+/// Files can either be opened through a jimage [Library] with [File::open], or parsed directly in Rust with
+/// [File::open_native], which doesn't require `jimage.dll` / `libjimage.so` at all.
+/// 
+/// [Library]:              struct.Library.html
+/// [File::open]:           #method.open
+/// [File::open_native]:    #method.open_native
 pub struct File {
-    api:    Arc<sys::Library>,
-    file:   AssertThreadSafe<*mut sys::JImageFile>,
+    path:       Option<PathBuf>, // None if parsed from memory
+    backend:    Backend,
+    index:      OnceLock<index::Index>, // built on first use by modules(), packages_of(), etc.
+}
+
+enum Backend {
+    Library {
+        path:   PathBuf,
+        api:    Arc<sys::Library>,
+        file:   AssertThreadSafe<*mut sys::JImageFile>,
+    },
+    Native(image::Image),
+    Jmod(jmod::Jmod),
 }
 fn _assert_file_is_send(file: &File) -> &dyn Send { file }
 fn _assert_file_is_sync(file: &File) -> &dyn Sync { file }
 
 impl File {
     /// Open a jimage-format file such as `jdk-13.0.1.9-hotspot/lib/modules`
+    /// 
+    /// ## Untrusted files
+    /// 
+    /// libjimage trusts the offsets and sizes within the file, so a malformed file can crash the process.  Files from
+    /// untrusted sources should instead be opened with [File::open_native], [File::open_mmap], or [File::from_bytes],
+    /// which bounds check everything - and can be checked up front with [File::verify].
+    /// 
+    /// [File::open_native]:    #method.open_native
+    /// [File::open_mmap]:      #method.open_mmap
+    /// [File::from_bytes]:     #method.from_bytes
+    /// [File::verify]:         #method.verify
     pub fn open(api: &Library, path: impl AsRef<Path>) -> Result<Self> {
         let orig = path.as_ref();
-        let path = orig.to_str().ok_or_else(|| Error::new(ErrorKind::InvalidInput, format!("File::open(api, {:?}) failed: couldn't convert path UTF8", orig)))?;
+        let path = orig.to_str().ok_or_else(|| Error::invalid_input(format!("File::open(api, {:?}) failed: couldn't convert path UTF8", orig)))?;
         let mut path = path.bytes().map(|b| b as c_char).collect::<Vec<c_char>>();
         path.push(0);
 
         let mut err = 0;
         let file = unsafe { (api.0.JIMAGE_Open)(path.as_ptr(), &mut err) };
-        if file == null_mut() { return Err(ji2io(format!("File::open(api, {:?}) failed", path), err)); }
+        if file.is_null() {
+            // libjimage reports most failures as JIMAGE_NOT_FOUND, and never which version it found, so check ourselves
+            let mut header = [0; Header::SIZE];
+            let err = match std::fs::File::open(orig).and_then(|mut f| f.read_exact(&mut header)) {
+                Err(err) if err.kind() == ErrorKind::UnexpectedEof => Error::BadMagic { path: None },
+                Err(err) => Error::from(err),
+                Ok(()) => match Header::read(&header) {
+                    Err(err) => err,
+                    Ok(header) if (u32::from(header.major_version), u32::from(header.minor_version)) != (image::MAJOR_VERSION, image::MINOR_VERSION) => {
+                        Error::BadVersion { path: None, major: header.major_version, minor: header.minor_version }
+                    },
+                    Ok(header) if header.byte_order != ByteOrder::NATIVE => {
+                        Error::Corrupted { path: None, resource: None, message: format!("JIMAGE_Open failed ({}): libjimage only reads host byte order images, open {:?} images with File::open_native", err, header.byte_order) }
+                    },
+                    Ok(_) => Error::Corrupted { path: None, resource: None, message: format!("JIMAGE_Open failed ({})", err) },
+                },
+            };
+            return Err(err.with_path(orig));
+        }
 
         // Safety:  I've taken a quick audit of jimage's C++ source code.  Once you look past the initial C entry
         // points, it quickly starts using `const` appropriately.  Parsing is up front, all the getters are nice and
@@ -84,68 +180,413 @@ impl File {
         let file = unsafe { AssertThreadSafe::new(file) };
 
         Ok(Self{
-            api: Arc::clone(&api.0),
-            file,
+            path: Some(orig.to_path_buf()),
+            backend: Backend::Library {
+                path: orig.to_path_buf(),
+                api: Arc::clone(&api.0),
+                file,
+            },
+            index: OnceLock::new(),
         })
     }
 
+    /// Open a jimage-format file such as `jdk-13.0.1.9-hotspot/lib/modules` without using a jimage [Library].
+    /// 
+    /// The file is read into memory and parsed in Rust, so this works even when no matching `jimage.dll` /
+    /// `libjimage.so` is available - stripped JREs, images from other architectures, machines without a JDK, etc.
+    /// 
+    /// [Library]:              struct.Library.html
+    pub fn open_native(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).map_err(|err| Error::from(err).with_path(path))?;
+        let image = image::Image::new(image::Data::Owned(data)).map_err(|err| err.with_path(path))?;
+        Ok(Self{ path: Some(path.to_path_buf()), backend: Backend::Native(image), index: OnceLock::new() })
+    }
+
+    /// Parse an in-memory jimage-format file without using a jimage [Library].
+    /// 
+    /// Like [File::open_native], every offset and size within the image is bounds checked, so this is suitable for
+    /// untrusted data - fuzzers, images extracted from user-supplied archives, etc.
+    /// 
+    /// [Library]:              struct.Library.html
+    /// [File::open_native]:    #method.open_native
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        let image = image::Image::new(image::Data::Owned(bytes))?;
+        Ok(Self{ path: None, backend: Backend::Native(image), index: OnceLock::new() })
+    }
+
+    /// Memory map a jimage-format file such as `jdk-13.0.1.9-hotspot/lib/modules` without using a jimage [Library].
+    /// 
+    /// Like [File::open_native], but instead of reading the whole file up front, it's mapped into memory and paged in
+    /// on demand.  Uncompressed resources can then be borrowed directly from the mapping with [Resource::bytes],
+    /// without copying or allocating.
+    /// 
+    /// ## Soundness
+    /// 
+    /// Mapping a file is only sound so long as nothing truncates or modifies it while it's mapped.  Much like the JVM
+    /// itself - which maps `lib/modules` the same way - this assumes that JDK images aren't being rewritten out from
+    /// under anyone using them.  Violating that assumption would reasonably be considered a bug in whatever is
+    /// modifying the image, so I consider it reasonable to have this method marked safe.
+    /// 
+    /// [Library]:              struct.Library.html
+    /// [File::open_native]:    #method.open_native
+    /// [Resource::bytes]:      struct.Resource.html#method.bytes
+    pub fn open_mmap(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let context = |err: std::io::Error| Error::from(err).with_path(path);
+        let file = std::fs::File::open(path).map_err(context)?;
+        let map = unsafe { memmap2::Mmap::map(&file) }.map_err(context)?; // Safety: see "Soundness" above
+        let image = image::Image::new(image::Data::Mapped(map)).map_err(|err| err.with_path(path))?;
+        Ok(Self{ path: Some(path.to_path_buf()), backend: Backend::Native(image), index: OnceLock::new() })
+    }
+
+    /// Open a `.jmod` file such as `jdk-13.0.1.9-hotspot/jmods/java.base.jmod`, exposing its `classes/` section as
+    /// the resources of its module - see [File::from_jmod].
+    /// 
+    /// [File::from_jmod]:      #method.from_jmod
+    pub fn open_jmod(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::from_jmod(jmod::Jmod::open(path)?))
+    }
+
+    /// Expose the `classes/` section of a [Jmod] as the resources of its module, such that `classes/java/lang/Object.class`
+    /// of `java.base.jmod` is found as `/java.base/java/lang/Object.class`.
+    /// 
+    /// The other sections (`conf/`, `lib/`, `bin/`, ...) aren't resources of the module, but remain accessible through
+    /// the [Jmod] itself.  jmods don't have a jimage [Header], so [File::header] fails with [Error::Unsupported].
+    /// 
+    /// [Jmod]:                 jmod/struct.Jmod.html
+    /// [Header]:               struct.Header.html
+    /// [File::header]:         #method.header
+    /// [Error::Unsupported]:   enum.Error.html#variant.Unsupported
+    pub fn from_jmod(jmod: jmod::Jmod) -> Self {
+        Self{ path: jmod.path().map(Path::to_path_buf), backend: Backend::Jmod(jmod), index: OnceLock::new() }
+    }
+
+    /// Read the [Header] of this file, describing its version, resource count, and the layout of its index.
+    /// 
+    /// [Library]-backed files don't expose their header, so for those the header is re-read from [File::path].
+    /// 
+    /// [Header]:               struct.Header.html
+    /// [Library]:              struct.Library.html
+    /// [File::path]:           #method.path
+    pub fn header(&self) -> Result<Header> {
+        match &self.backend {
+            Backend::Native(image) => Ok(*image.header()),
+            Backend::Library { path, .. } => {
+                let mut bytes = [0; Header::SIZE];
+                std::fs::File::open(path).and_then(|mut f| f.read_exact(&mut bytes)).map_err(|err| Error::from(err).with_path(path.as_path()))?;
+                Header::read(&bytes).map_err(|err| err.with_path(path.as_path()))
+            },
+            Backend::Jmod(_) => Err(Error::Unsupported { message: "file.header() failed: jmod files don't have a jimage header".into() }),
+        }
+    }
+
+    /// Check the integrity of the entire file, returning a report of every problem found.
+    /// 
+    /// Every location is checked to be in bounds, to hash back to its own slot (so it can be found by name), and -
+    /// if compressed - to decompress to its declared size.  Finding problems isn't an error, but failing to read the
+    /// file at all is.  [Library]-backed files are verified by mapping [File::path] with [File::open_mmap].  For jmod
+    /// files, every entry of every section is checked to decompress to its declared size and CRC instead.
+    /// 
+    /// [Library]:              struct.Library.html
+    /// [File::path]:           #method.path
+    /// [File::open_mmap]:      #method.open_mmap
+    pub fn verify(&self) -> Result<VerifyReport> {
+        match &self.backend {
+            Backend::Native(image) => Ok(verify::verify(image)),
+            Backend::Library { path, .. } => File::open_mmap(path)?.verify(),
+            Backend::Jmod(jmod) => Ok(verify::verify_jmod(jmod)),
+        }
+    }
+
+    /// Extract every resource matching `filter` to `dir/module/package/name.ext`, creating directories as needed and
+    /// overwriting existing files.  Returns how many resources were extracted.
+    /// 
+    /// Resources are extracted in sorted order (by module, then path), so the same image and filter always write the
+    /// same files in the same order.  Resource paths that would escape `dir` (e.g. via a `..` segment) fail with
+    /// [Error::Corrupted] before anything is written for them.
+    /// 
+    /// [Error::Corrupted]:     enum.Error.html#variant.Corrupted
+    pub fn extract_to(&self, dir: impl AsRef<Path>, filter: &ExtractFilter) -> Result<usize> {
+        extract::extract_to(self, dir.as_ref(), filter, &mut |_| {})
+    }
+
+    /// [File::extract_to], calling `progress` after each resource is written.
+    /// 
+    /// [File::extract_to]:     #method.extract_to
+    pub fn extract_to_with_progress(&self, dir: impl AsRef<Path>, filter: &ExtractFilter, mut progress: impl FnMut(ExtractProgress)) -> Result<usize> {
+        extract::extract_to(self, dir.as_ref(), filter, &mut progress)
+    }
+
+    /// Write a module ("java.base") as a `.jmod` file, returning how many resources were exported.
+    /// 
+    /// Every resource of the module - `module-info.class` included - is written to the jmod's `classes/` section.
+    /// Runtime images don't retain the other sections (`conf/`, `lib/`, `bin/`, `legal/`, ...) as resources, so those
+    /// aren't reconstituted.  Output is deterministic: entries are written in sorted order with a fixed timestamp.
+    /// 
+    /// Runtime images already contain the `java.lang.invoke` classes jlink's `generate-jli-classes` plugin generates,
+    /// so relinking an exported `java.base` requires `jlink --disable-plugin generate-jli-classes`.
+    pub fn export_jmod(&self, module: &str, mut writer: impl Write) -> Result<usize> {
+        jmod::export(self, module, &mut writer)
+    }
+
+    /// The path this file was opened from, or `None` if it was parsed from memory with [File::from_bytes]
+    /// 
+    /// [File::from_bytes]:     #method.from_bytes
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
     /// Map a package ("java/lang") to a module ("java.base")
     pub fn package_to_module<'s>(&'s self, package_name: &CStr) -> Result<&'s CStr> {
-        let result = unsafe { (self.api.JIMAGE_PackageToModule)(*self.file, package_name.as_ptr()) };
-        if result != null() {
-            Ok(unsafe { CStr::from_ptr(result) }) // C string lasts as long as th file does
-        } else {
-            Err(Error::new(ErrorKind::NotFound, format!("file.package_to_module({:?}) failed: no such package", package_name)))
+        let not_found = || Error::not_found(package_name.to_string_lossy(), "no such package").with_path(self.path.as_deref());
+        match &self.backend {
+            Backend::Library { api, file, .. } => {
+                let result = unsafe { (api.JIMAGE_PackageToModule)(**file, package_name.as_ptr()) };
+                if !result.is_null() {
+                    Ok(unsafe { CStr::from_ptr(result) }) // C string lasts as long as th file does
+                } else {
+                    Err(not_found())
+                }
+            },
+            Backend::Native(image) => {
+                // "/packages/java.lang" contains (is_empty: u4, module_name_offset: u4) pairs, use the first non-empty module
+                let mut path = b"/packages/".to_vec();
+                path.extend(package_name.to_bytes().iter().map(|&b| if b == b'/' { b'.' } else { b }));
+                let location = image.find_location(&path).ok_or_else(not_found)?;
+                let content = image.resource(&location).map_err(|err| err.with_path(self.path.as_deref()))?;
+                let module = content.chunks_exact(8).find(|pair| image.u4(&pair[..4]) == 0).map_or(0, |pair| image.u4(&pair[4..]));
+                image.string(u64::from(module)).ok_or_else(|| Error::corrupted(format!("module name offset {} is out of bounds", module)).with_resource(String::from_utf8_lossy(&path)).with_path(self.path.as_deref()))
+            },
+            Backend::Jmod(jmod) => {
+                let package = package_name.to_str().map_err(|_| not_found())?;
+                jmod.packages().binary_search_by(|p| p.as_str().cmp(package)).map(|_| jmod.module_cstr()).map_err(|_| not_found())
+            },
         }
     }
 
+    /// List the modules ("java.base", "java.logging", ...) of the file, in sorted order.
+    /// 
+    /// The first call to this or any of [File::packages_of], [File::resources_of], or [File::module_to_packages]
+    /// enumerates every resource of the file once, to build an index reused by all later calls.
+    /// 
+    /// [File::packages_of]:        #method.packages_of
+    /// [File::resources_of]:       #method.resources_of
+    /// [File::module_to_packages]: #method.module_to_packages
+    pub fn modules(&self) -> impl ExactSizeIterator<Item = &str> + '_ {
+        self.index().resources.keys().map(|m| m.as_str())
+    }
+
+    /// List the packages ("java/lang", "java/util", ...) of a module ("java.base"), in sorted order.
+    pub fn packages_of(&self, module_name: &str) -> Result<&[String]> {
+        self.index().packages.get(module_name).map(|p| &p[..]).ok_or_else(|| Error::not_found(module_name, "no such module").with_path(self.path.as_deref()))
+    }
+
+    /// List the resources of a module ("java.base"), in sorted order.
+    pub fn resources_of(&self, module_name: &str) -> Result<&[ResourceEntry]> {
+        self.index().resources.get(module_name).map(|r| &r[..]).ok_or_else(|| Error::not_found(module_name, "no such module").with_path(self.path.as_deref()))
+    }
+
+    /// Map every module ("java.base") to its packages ("java/lang", "java/util", ...) - the reverse of
+    /// [File::package_to_module].
+    /// 
+    /// [File::package_to_module]:  #method.package_to_module
+    pub fn module_to_packages(&self) -> &BTreeMap<String, Vec<String>> {
+        &self.index().packages
+    }
+
+    fn index(&self) -> &index::Index {
+        self.index.get_or_init(|| index::Index::new(self.resources()))
+    }
+
     /// Map a module ("java.base"), version ("9.0"), and name ("java/lang/Object.class") to a size + location.
     pub fn find_resource<'s>(&'s self, module_name: &CStr, version: &CStr, name: &CStr) -> Result<Resource<'s>> {
-        let mut size = 0;
-        let result = unsafe { (self.api.JIMAGE_FindResource)(*self.file, module_name.as_ptr(), version.as_ptr(), name.as_ptr(), &mut size) };
-        if result <= 0 {
-            Err(ji2io(format!("file.find_resource({:?}, {:?}, {:?}) failed", module_name, version, name), result))
-        } else {
-            Ok(Resource{
-                file:       self,
-                location:   result,
-                size:       size as u64,
-            })
+        let resource = || format!("/{}/{}", module_name.to_string_lossy(), name.to_string_lossy());
+        match &self.backend {
+            Backend::Library { api, file, .. } => {
+                let mut size = 0;
+                let result = unsafe { (api.JIMAGE_FindResource)(**file, module_name.as_ptr(), version.as_ptr(), name.as_ptr(), &mut size) };
+                if result <= 0 {
+                    Err(Error::from_jimage(result, resource(), self.path.as_deref()))
+                } else {
+                    Ok(Resource{
+                        file:       self,
+                        location:   ResourceLocation::Library(result),
+                        size:       size as u64,
+                    })
+                }
+            },
+            Backend::Native(image) => {
+                // Like libjimage, the version is ignored: resources are looked up by "/module/name"
+                let mut path = Vec::with_capacity(module_name.to_bytes().len() + name.to_bytes().len() + 2);
+                path.push(b'/');
+                path.extend_from_slice(module_name.to_bytes());
+                path.push(b'/');
+                path.extend_from_slice(name.to_bytes());
+                match image.find_location(&path) {
+                    None => Err(Error::not_found(resource(), "no such resource").with_path(self.path.as_deref())),
+                    Some(location) => Ok(Resource{
+                        file:       self,
+                        location:   ResourceLocation::Native(location),
+                        size:       location.get(image::ATTRIBUTE_UNCOMPRESSED),
+                    }),
+                }
+            },
+            Backend::Jmod(jmod) => {
+                // Like libjimage, the version is ignored
+                let found = if module_name == jmod.module_cstr() { name.to_str().ok().and_then(|name| jmod.find_resource(name)) } else { None };
+                match found {
+                    None => Err(Error::not_found(resource(), "no such resource").with_path(self.path.as_deref())),
+                    Some(index) => Ok(Resource{
+                        file:       self,
+                        location:   ResourceLocation::Jmod(index),
+                        size:       jmod.entry(index).size(),
+                    }),
+                }
+            },
         }
     }
 
-    /// Enumerate all resources of the file so long as the callback returns VisitResult::Continue.
-    pub fn visit<F: FnMut(VisitParams) -> VisitResult>(&self, f: F) {
-        unsafe extern "C" fn visit<F: FnMut(VisitParams) -> VisitResult>(_image: *mut sys::JImageFile, module_name: *const c_char, version: *const c_char, package: *const c_char, name: *const c_char, extension: *const c_char, arg: *mut c_void) -> bool {
-            let context = &mut *(arg as *mut VisitContext::<F>);
-            (context.f)(VisitParams {
-                file:           context.file,
-                module_name:    CStr::from_ptr(module_name),
-                version:        CStr::from_ptr(version),
-                package:        CStr::from_ptr(package),
-                name:           CStr::from_ptr(name),
-                extension:      CStr::from_ptr(extension),
-            }) == VisitResult::Continue
+    /// Find a resource by its module-qualified path, such as `"/java.base/java/lang/Object.class"` or
+    /// `"java.base/java/lang/Object.class"`.
+    pub fn find(&self, path: &str) -> Result<Resource<'_>> {
+        self.find_path(&ResourcePath::parse(path)?)
+    }
+
+    /// Find a resource by its module-qualified [ResourcePath](struct.ResourcePath.html).
+    pub fn find_path(&self, path: &ResourcePath) -> Result<Resource<'_>> {
+        // ResourcePath s can't contain NULs
+        let module  = CString::new(path.module()).unwrap();
+        let name    = CString::new(path.path()).unwrap();
+        self.find_resource(&module, CStr::from_bytes_with_nul(image::VERSION).unwrap(), &name)
+    }
+
+    /// Find a class by its binary name, such as `"java.lang.String"`, `"java/util/Map$Entry"`, or `"java.util.Map$Entry"`.
+    /// 
+    /// The module is resolved with [File::package_to_module].  Both an unknown package and a known package without
+    /// the class in question fail with [ErrorKind::NotFound]: the former as [Error::UnknownPackage], the latter as
+    /// [Error::NotFound].
+    /// 
+    /// [File::package_to_module]:  #method.package_to_module
+    /// [ErrorKind::NotFound]:      https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.NotFound
+    /// [Error::UnknownPackage]:    enum.Error.html#variant.UnknownPackage
+    /// [Error::NotFound]:          enum.Error.html#variant.NotFound
+    pub fn find_class(&self, name: &str) -> Result<Resource<'_>> {
+        let class = name.replace('.', "/");
+        if class.is_empty() || class.starts_with('/') || class.ends_with('/') || class.contains('\0') {
+            return Err(Error::invalid_input(format!("file.find_class({:?}) failed: invalid class name", name)));
         }
-        let mut context = VisitContext {
-            file: self,
-            f,
+        let package = &class[..class.rfind('/').unwrap_or(0)];
+        let module = match self.package_to_module(&CString::new(package).unwrap()) { // class has no NULs
+            Ok(module) => module,
+            Err(Error::NotFound { .. }) => return Err(Error::UnknownPackage { path: None, class: name.into(), package: package.into() }.with_path(self.path.as_deref())),
+            Err(err) => return Err(err),
+        };
+        let path = CString::new(format!("{}.class", class)).unwrap();
+        match self.find_resource(module, CStr::from_bytes_with_nul(image::VERSION).unwrap(), &path) {
+            Err(Error::NotFound { .. }) => Err(Error::not_found(name, format!("no such class in package {:?} of module {:?}", package, module)).with_path(self.path.as_deref())),
+            result => result,
+        }
+    }
+
+    /// Enumerate all resources of the file.
+    /// 
+    /// Unlike [File::visit], this composes with iterator adapters and `?`, and can be paused or abandoned at any point.
+    /// Natively parsed files and jmods are enumerated lazily, whereas [Library]-backed files must enumerate all
+    /// resources when the first is requested, as `JIMAGE_ResourceIterator` only supports callbacks.
+    /// 
+    /// [File::visit]:          #method.visit
+    /// [Library]:              struct.Library.html
+    pub fn resources(&self) -> Resources<'_> {
+        let inner = match &self.backend {
+            Backend::Native(image)  => ResourcesInner::Native { image, index: 0 },
+            Backend::Jmod(jmod)     => ResourcesInner::Jmod { jmod, index: 0 },
+            Backend::Library { .. } => ResourcesInner::Library { entries: None },
         };
-        let context : *mut VisitContext::<F> = &mut context;
-        unsafe { (self.api.JIMAGE_ResourceIterator)(*self.file, visit::<F>, context as *mut c_void) };
+        Resources { file: self, inner }
+    }
+
+    /// Enumerate all resources of the file in parallel.
+    /// 
+    /// Like [File::resources], natively parsed files are enumerated lazily, split across threads by location, whereas
+    /// [Library]-backed files must enumerate all resources up front.
+    /// 
+    /// [File::resources]:      #method.resources
+    /// [Library]:              struct.Library.html
+    #[cfg(feature = "rayon")]
+    pub fn par_resources(&self) -> impl rayon::iter::ParallelIterator<Item = ResourceEntry> + '_ {
+        use rayon::iter::{Either, IntoParallelIterator, ParallelIterator};
+        match &self.backend {
+            Backend::Native(image) => Either::Left((0..image.table_length()).into_par_iter().filter_map(move |index| {
+                let (_, [module_name, package, name, extension]) = image.resource_names(index)?;
+                Some(ResourceEntry::new(module_name, CStr::from_bytes_with_nul(image::VERSION).unwrap(), package, name, extension))
+            })),
+            Backend::Library { .. } | Backend::Jmod(_) => Either::Right(self.resources().collect::<Vec<_>>().into_par_iter()),
+        }
+    }
+
+    /// Read every resource of the file in parallel, yielding each [ResourceEntry] alongside its contents.
+    /// 
+    /// This is the parallel equivalent of calling [Resource::bytes] on each of [File::resources], suitable for
+    /// extracting or hashing an entire image.
+    /// 
+    /// [ResourceEntry]:        struct.ResourceEntry.html
+    /// [Resource::bytes]:      struct.Resource.html#method.bytes
+    /// [File::resources]:      #method.resources
+    #[cfg(feature = "rayon")]
+    pub fn par_read_resources(&self) -> impl rayon::iter::ParallelIterator<Item = (ResourceEntry, Result<Cow<'_, [u8]>>)> + '_ {
+        use rayon::iter::ParallelIterator;
+        self.par_resources().map(move |entry| {
+            let bytes = entry.resource(self).and_then(|r| r.bytes());
+            (entry, bytes)
+        })
+    }
+
+    /// Enumerate all resources of the file so long as the callback returns VisitResult::Continue.
+    /// 
+    /// This is a thin layer over [File::resources], lending each resource's names to the callback instead of copying
+    /// them into a [ResourceEntry].
+    /// 
+    /// [File::resources]:      #method.resources
+    /// [ResourceEntry]:        struct.ResourceEntry.html
+    pub fn visit<F: FnMut(VisitParams) -> VisitResult>(&self, mut f: F) {
+        let mut resources = self.resources();
+        while resources.next_with(&mut f) == Some(VisitResult::Continue) {}
+    }
+
+    /// Every resource of a [Library]-backed file, via `JIMAGE_ResourceIterator`
+    /// 
+    /// [Library]:              struct.Library.html
+    fn library_entries(&self) -> Vec<ResourceEntry> {
+        let (api, file) = match &self.backend {
+            Backend::Library { api, file, .. } => (api, file),
+            _ => unreachable!("library_entries called on a File that isn't Library-backed"),
+        };
+
+        unsafe extern "C" fn visit(_image: *mut sys::JImageFile, module_name: *const c_char, version: *const c_char, package: *const c_char, name: *const c_char, extension: *const c_char, arg: *mut c_void) -> bool {
+            let entries = &mut *(arg as *mut Vec<ResourceEntry>);
+            entries.push(ResourceEntry::new(CStr::from_ptr(module_name), CStr::from_ptr(version), CStr::from_ptr(package), CStr::from_ptr(name), CStr::from_ptr(extension)));
+            true
+        }
+        let mut entries = Vec::new();
+        let entries_ptr : *mut Vec<ResourceEntry> = &mut entries;
+        unsafe { (api.JIMAGE_ResourceIterator)(**file, visit, entries_ptr as *mut c_void) };
+        entries
     }
 }
 
 impl Drop for File {
     fn drop(&mut self) {
-        unsafe { (self.api.JIMAGE_Close)(*self.file) };
+        if let Backend::Library { api, file, .. } = &self.backend {
+            unsafe { (api.JIMAGE_Close)(**file) };
+        }
     }
 }
 
 /// The location and size of a jimage resource such as `java/lang/Object.class`
 pub struct Resource<'file> {
     file:       &'file File,
-    location:   sys::JImageLocationRef,
+    location:   ResourceLocation,
     size:       u64,
     // I don't know if it's sound to mix sys::JImageLocationRef s with different files.
     // As such, this resource struct bundles it directly with the file that it belongs to,
@@ -153,27 +594,187 @@ pub struct Resource<'file> {
     // in question.
 }
 
-impl Resource<'_> {
+enum ResourceLocation {
+    Library(sys::JImageLocationRef),
+    Native(image::Location),
+    Jmod(usize),
+}
+
+impl<'file> Resource<'file> {
     /// How large this resource is in bytes
     pub fn size(&self) -> u64 { self.size }
 
+    /// How large this resource's stored content is if compressed, or `0` if it isn't.  `None` for [Library]-backed
+    /// files, as libjimage doesn't expose this.  For jmod files, this is the size of the deflated zip entry.
+    /// 
+    /// [Library]:              struct.Library.html
+    pub fn compressed_size(&self) -> Option<u64> {
+        match (&self.file.backend, &self.location) {
+            (_, ResourceLocation::Library(_))               => None,
+            (_, ResourceLocation::Native(location))         => Some(location.get(image::ATTRIBUTE_COMPRESSED)),
+            (Backend::Jmod(jmod), ResourceLocation::Jmod(index)) => Some(jmod.entry(*index).compressed_size()),
+            _ => unreachable!("Resource location doesn't match the File backend it was created from"),
+        }
+    }
+
+    /// The offset of this resource's stored content, relative to the end of the file's index (see
+    /// [Header::index_size]).  `None` for [Library]-backed files, as libjimage doesn't expose this, and for jmod
+    /// files, which have no such index.
+    /// 
+    /// [Library]:              struct.Library.html
+    /// [Header::index_size]:   struct.Header.html#method.index_size
+    pub fn offset(&self) -> Option<u64> {
+        match &self.location {
+            ResourceLocation::Library(_) | ResourceLocation::Jmod(_) => None,
+            ResourceLocation::Native(location)  => Some(location.get(image::ATTRIBUTE_OFFSET)),
+        }
+    }
+
+    /// The module-qualified path of this resource (e.g. `/java.base/java/lang/Object.class`)
+    /// 
+    /// [Library]-backed files only support this if the library exports `JIMAGE_ResourcePath` (JDK 9 - 12), see
+    /// [Library::capabilities].
+    /// 
+    /// [Library]:              struct.Library.html
+    /// [Library::capabilities]: struct.Library.html#method.capabilities
+    pub fn path(&self) -> Result<ResourcePath> {
+        let name = match (&self.file.backend, &self.location) {
+            (Backend::Library { api, file, .. }, ResourceLocation::Library(location)) => {
+                let resource_path = api.JIMAGE_ResourcePath.ok_or_else(|| Error::Unsupported { message: "resource.path() requires JIMAGE_ResourcePath, which this jimage library doesn't export (JDK 13+)".into() })?;
+                let mut buffer = vec![0 as c_char; 4096]; // JIMAGE_MAX_PATH
+                if !unsafe { resource_path(**file, *location, buffer.as_mut_ptr(), buffer.len()) } {
+                    return Err(Error::from_jimage(sys::JIMAGE_CORRUPTED, format_args!("at location {}", location), self.file.path.as_deref()));
+                }
+                unsafe { CStr::from_ptr(buffer.as_ptr()) }.to_bytes().to_vec()
+            },
+            (Backend::Native(image), ResourceLocation::Native(location)) => {
+                image.full_name(location).ok_or_else(|| Error::corrupted("resource name is out of bounds").with_path(self.file.path.as_deref()))?
+            },
+            (Backend::Jmod(jmod), ResourceLocation::Jmod(index)) => {
+                return ResourcePath::new(jmod.module_name(), jmod.entry(*index).name());
+            },
+            _ => unreachable!("Resource location doesn't match the File backend it was created from"),
+        };
+        let name = String::from_utf8(name).map_err(|err| Error::NonUtf8 { what: "resource path", bytes: err.into_bytes() })?;
+        ResourcePath::parse(&name)
+    }
+
     /// Read the raw bytes of this resource into the given buffer
+    /// 
+    /// If the buffer is smaller than [Resource::size], only the first `buffer.len()` bytes are read.
+    /// 
+    /// [Resource::size]:       #method.size
     pub fn get(&self, buffer: &mut [u8]) -> Result<u64> {
-        let len = (buffer.len() as u64).min(std::i64::MAX as u64) as i64;
-        let result = unsafe { (self.file.api.JIMAGE_GetResource)(*self.file.file, self.location, buffer.as_mut_ptr() as *mut _, len) };
-        if result < 0 {
-            Err(ji2io("resource.get(...) failed", result))
-        } else {
-            Ok(result as u64)
+        match (&self.file.backend, &self.location) {
+            (Backend::Library { api, file, .. }, ResourceLocation::Library(location)) => {
+                // JIMAGE_GetResource ignores the buffer size and always writes the entire resource, so never give it
+                // a buffer smaller than that.
+                if (buffer.len() as u64) < self.size {
+                    let all = self.read_to_vec()?;
+                    buffer.copy_from_slice(&all[..buffer.len()]);
+                    return Ok(buffer.len() as u64);
+                }
+                let len = (buffer.len() as u64).min(i64::MAX as u64) as i64;
+                let result = unsafe { (api.JIMAGE_GetResource)(**file, *location, buffer.as_mut_ptr() as *mut _, len) };
+                if result < 0 {
+                    Err(Error::from_jimage(result, format_args!("at location {}", location), self.file.path.as_deref()))
+                } else {
+                    Ok((result as u64).min(self.size))
+                }
+            },
+            (Backend::Native(_), ResourceLocation::Native(_)) | (Backend::Jmod(_), ResourceLocation::Jmod(_)) => {
+                let content = self.bytes()?;
+                let n = content.len().min(buffer.len());
+                buffer[..n].copy_from_slice(&content[..n]);
+                Ok(n as u64)
+            },
+            _ => unreachable!("Resource location doesn't match the File backend it was created from"),
+        }
+    }
+
+    /// Read the entire resource into a new `Vec`
+    pub fn read_to_vec(&self) -> Result<Vec<u8>> {
+        Ok(self.bytes()?.into_owned())
+    }
+
+    /// Write the entire resource to `w`, returning the number of bytes written
+    pub fn copy_to(&self, w: &mut impl Write) -> Result<u64> {
+        let bytes = self.bytes()?;
+        w.write_all(&bytes)?;
+        Ok(bytes.len() as u64)
+    }
+
+    /// Read the resource into a [ResourceReader], which implements [Read], [BufRead], and [Seek]
+    /// 
+    /// [ResourceReader]:       struct.ResourceReader.html
+    /// [Read]:                 https://doc.rust-lang.org/std/io/trait.Read.html
+    /// [BufRead]:              https://doc.rust-lang.org/std/io/trait.BufRead.html
+    /// [Seek]:                 https://doc.rust-lang.org/std/io/trait.Seek.html
+    pub fn reader(&self) -> Result<ResourceReader<'file>> {
+        Ok(ResourceReader(Cursor::new(self.bytes()?)))
+    }
+
+    /// The entire contents of this resource
+    /// 
+    /// Uncompressed resources of files opened with [File::open_mmap], [File::open_native], or [File::open_jmod] are
+    /// borrowed directly from the file without copying.  Compressed resources, and resources of [Library]-backed
+    /// files, are read into a new buffer.
+    /// 
+    /// [File::open_mmap]:      struct.File.html#method.open_mmap
+    /// [File::open_native]:    struct.File.html#method.open_native
+    /// [File::open_jmod]:      struct.File.html#method.open_jmod
+    /// [Library]:              struct.Library.html
+    pub fn bytes(&self) -> Result<Cow<'file, [u8]>> {
+        match (&self.file.backend, &self.location) {
+            (Backend::Library { .. }, ResourceLocation::Library(_)) => {
+                let size = usize::try_from(self.size).map_err(|_| Error::corrupted(format!("resource of {} bytes is too large to read into memory", self.size)).with_path(self.file.path.as_deref()))?;
+                let mut buffer = vec![0; size];
+                let read = self.get(&mut buffer)? as usize;
+                buffer.truncate(read);
+                Ok(Cow::Owned(buffer))
+            },
+            (Backend::Native(image), ResourceLocation::Native(location)) => {
+                image.resource(location).map_err(|err| err.with_path(self.file.path.as_deref()))
+            },
+            (Backend::Jmod(jmod), ResourceLocation::Jmod(index)) => jmod.read(jmod.entry(*index)),
+            _ => unreachable!("Resource location doesn't match the File backend it was created from"),
         }
     }
 }
 
+/// An in-memory reader over the contents of a [Resource], returned by [Resource::reader]
+/// 
+/// [Resource]:             struct.Resource.html
+/// [Resource::reader]:     struct.Resource.html#method.reader
+pub struct ResourceReader<'file>(Cursor<Cow<'file, [u8]>>);
+
+impl ResourceReader<'_> {
+    /// The entire contents of the resource, regardless of the current position
+    pub fn get_ref(&self) -> &[u8] { self.0.get_ref() }
+    /// The current position within the resource
+    pub fn position(&self) -> u64 { self.0.position() }
+}
+
+impl Read for ResourceReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> { self.0.read(buf) }
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> std::io::Result<usize> { self.0.read_to_end(buf) }
+}
+
+impl BufRead for ResourceReader<'_> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> { self.0.fill_buf() }
+    fn consume(&mut self, amt: usize) { self.0.consume(amt) }
+}
+
+impl Seek for ResourceReader<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> { self.0.seek(pos) }
+}
+
 /// The parameters to [File::visit]
 /// 
 /// [File::visit]:          struct.File.html#method.visit
 pub struct VisitParams<'file> {
     file:           &'file File,
+    location:       Option<image::Location>, // natively parsed files already know where the resource is
     module_name:    &'file CStr,
     version:        &'file CStr,
     package:        &'file CStr,
@@ -194,25 +795,173 @@ impl<'file> VisitParams<'file> {
     pub fn extension_cstr(&self)    -> &'file CStr { self.extension }
 
     /// The module name (e.g. `"java.base"`)
-    pub fn module_name(&self)   -> Result<&'file str> { self.module_name    .to_str().map_err(|_| Error::new(ErrorKind::InvalidData, format!("module_name {:?} isn't valid UTF8",   self.module_name  ))) }
+    pub fn module_name(&self)   -> Result<&'file str> { self.module_name    .to_str().map_err(|_| Error::NonUtf8 { what: "module_name", bytes: self.module_name.to_bytes().to_vec() }) }
     /// The module version (e.g. `"9"` or `"9.0"`)
-    pub fn version(&self)       -> Result<&'file str> { self.version        .to_str().map_err(|_| Error::new(ErrorKind::InvalidData, format!("version {:?} isn't valid UTF8",       self.version      ))) }
+    pub fn version(&self)       -> Result<&'file str> { self.version        .to_str().map_err(|_| Error::NonUtf8 { what: "version", bytes: self.version.to_bytes().to_vec() }) }
     /// The package (e.g. `"java/lang"`)
-    pub fn package(&self)       -> Result<&'file str> { self.package        .to_str().map_err(|_| Error::new(ErrorKind::InvalidData, format!("package {:?} isn't valid UTF8",       self.package      ))) }
+    pub fn package(&self)       -> Result<&'file str> { self.package        .to_str().map_err(|_| Error::NonUtf8 { what: "package", bytes: self.package.to_bytes().to_vec() }) }
     /// The name (e.g. `"OuterClass$InnerClass"`)
-    pub fn name(&self)          -> Result<&'file str> { self.name           .to_str().map_err(|_| Error::new(ErrorKind::InvalidData, format!("name {:?} isn't valid UTF8",          self.name         ))) }
+    pub fn name(&self)          -> Result<&'file str> { self.name           .to_str().map_err(|_| Error::NonUtf8 { what: "name", bytes: self.name.to_bytes().to_vec() }) }
     /// The file extension (e.g. `"class"`)
-    pub fn extension(&self)     -> Result<&'file str> { self.extension      .to_str().map_err(|_| Error::new(ErrorKind::InvalidData, format!("extension {:?} isn't valid UTF8",     self.extension    ))) }
+    pub fn extension(&self)     -> Result<&'file str> { self.extension      .to_str().map_err(|_| Error::NonUtf8 { what: "extension", bytes: self.extension.to_bytes().to_vec() }) }
+
+    /// The module-qualified path of this resource (e.g. `/java.base/java/lang/Object.class`)
+    pub fn path(&self) -> Result<ResourcePath> {
+        ResourcePath::new(self.module_name()?, resource_name(self.package, self.name, self.extension)?.into_string().unwrap())
+    }
 
     /// Get a resource handle allowing you to read the file in question
     pub fn resource(&self) -> Result<Resource<'file>> {
-        self.file.find_resource(self.module_name, self.version, CStr::from_bytes_with_nul(format!(
-            "{}/{}.{}\0",
-            self.package    .to_str().map_err(|_| Error::new(ErrorKind::InvalidData, format!("resource package name {:?} isn't valid UTF8",   self.package    )))?,
-            self.name       .to_str().map_err(|_| Error::new(ErrorKind::InvalidData, format!("resource name {:?} isn't valid UTF8",           self.name       )))?,
-            self.extension  .to_str().map_err(|_| Error::new(ErrorKind::InvalidData, format!("resource extension {:?} isn't valid UTF8",      self.extension  )))?,
-        ).as_bytes()).unwrap())
+        if let Some(location) = self.location {
+            return Ok(Resource { file: self.file, location: ResourceLocation::Native(location), size: location.get(image::ATTRIBUTE_UNCOMPRESSED) });
+        }
+        self.file.find_resource(self.module_name, self.version, &resource_name(self.package, self.name, self.extension)?)
     }
+
+    /// Copy these parameters into an owned [ResourceEntry](struct.ResourceEntry.html)
+    pub fn to_entry(&self) -> ResourceEntry {
+        ResourceEntry::new(self.module_name, self.version, self.package, self.name, self.extension)
+    }
+}
+
+/// An owned resource name, as enumerated by [File::resources]
+/// 
+/// [File::resources]:      struct.File.html#method.resources
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ResourceEntry {
+    module_name:    CString,
+    version:        CString,
+    package:        CString,
+    name:           CString,
+    extension:      CString,
+}
+
+impl ResourceEntry {
+    fn new(module_name: &CStr, version: &CStr, package: &CStr, name: &CStr, extension: &CStr) -> Self {
+        Self {
+            module_name:    module_name.to_owned(),
+            version:        version.to_owned(),
+            package:        package.to_owned(),
+            name:           name.to_owned(),
+            extension:      extension.to_owned(),
+        }
+    }
+
+    /// The module name (e.g. `"java.base"`)
+    pub fn module_name_cstr(&self)  -> &CStr { &self.module_name }
+    /// The module version (e.g. `"9"` or `"9.0"`)
+    pub fn version_cstr(&self)      -> &CStr { &self.version }
+    /// The package (e.g. `"java/lang"`)
+    pub fn package_cstr(&self)      -> &CStr { &self.package }
+    /// The name (e.g. `"OuterClass$InnerClass"`)
+    pub fn name_cstr(&self)         -> &CStr { &self.name }
+    /// The file extension (e.g. `"class"`)
+    pub fn extension_cstr(&self)    -> &CStr { &self.extension }
+
+    /// The module name (e.g. `"java.base"`)
+    pub fn module_name(&self)   -> Result<&str> { self.module_name  .to_str().map_err(|_| Error::NonUtf8 { what: "module_name", bytes: self.module_name.to_bytes().to_vec() }) }
+    /// The module version (e.g. `"9"` or `"9.0"`)
+    pub fn version(&self)       -> Result<&str> { self.version      .to_str().map_err(|_| Error::NonUtf8 { what: "version", bytes: self.version.to_bytes().to_vec() }) }
+    /// The package (e.g. `"java/lang"`)
+    pub fn package(&self)       -> Result<&str> { self.package      .to_str().map_err(|_| Error::NonUtf8 { what: "package", bytes: self.package.to_bytes().to_vec() }) }
+    /// The name (e.g. `"OuterClass$InnerClass"`)
+    pub fn name(&self)          -> Result<&str> { self.name         .to_str().map_err(|_| Error::NonUtf8 { what: "name", bytes: self.name.to_bytes().to_vec() }) }
+    /// The file extension (e.g. `"class"`)
+    pub fn extension(&self)     -> Result<&str> { self.extension    .to_str().map_err(|_| Error::NonUtf8 { what: "extension", bytes: self.extension.to_bytes().to_vec() }) }
+
+    /// The module-qualified path of this resource (e.g. `/java.base/java/lang/Object.class`)
+    pub fn path(&self) -> Result<ResourcePath> {
+        ResourcePath::new(self.module_name()?, resource_name(&self.package, &self.name, &self.extension)?.into_string().unwrap())
+    }
+
+    /// Get a resource handle from the file this entry was enumerated from, allowing you to read the file in question
+    pub fn resource<'file>(&self, file: &'file File) -> Result<Resource<'file>> {
+        file.find_resource(&self.module_name, &self.version, &resource_name(&self.package, &self.name, &self.extension)?)
+    }
+}
+
+/// An iterator over the [ResourceEntry]s of a [File], returned by [File::resources]
+/// 
+/// [File]:                 struct.File.html
+/// [File::resources]:      struct.File.html#method.resources
+/// [ResourceEntry]:        struct.ResourceEntry.html
+pub struct Resources<'file> {
+    file:   &'file File,
+    inner:  ResourcesInner<'file>,
+}
+
+enum ResourcesInner<'file> {
+    Native  { image: &'file image::Image, index: u32 },
+    Jmod    { jmod: &'file jmod::Jmod, index: usize },
+    Library { entries: Option<std::vec::IntoIter<ResourceEntry>> }, // None until the first resource is requested
+}
+
+impl Resources<'_> {
+    /// Lend the next resource's names to `f`, without copying them - what [File::visit] is built on
+    /// 
+    /// [File::visit]:          struct.File.html#method.visit
+    fn next_with<R>(&mut self, f: impl FnOnce(VisitParams) -> R) -> Option<R> {
+        let file = self.file;
+        let version = CStr::from_bytes_with_nul(image::VERSION).unwrap();
+        match &mut self.inner {
+            ResourcesInner::Native { image, index } => {
+                while *index < image.table_length() {
+                    let names = image.resource_names(*index);
+                    *index += 1;
+                    if let Some((location, [module_name, package, name, extension])) = names {
+                        return Some(f(VisitParams { file, location: Some(location), module_name, version, package, name, extension }));
+                    }
+                }
+                None
+            },
+            ResourcesInner::Jmod { jmod, index } => {
+                let r = jmod.resources().get(*index)?;
+                *index += 1;
+                Some(f(VisitParams { file, location: None, module_name: jmod.module_cstr(), version, package: &r.package, name: &r.name, extension: &r.extension }))
+            },
+            ResourcesInner::Library { entries } => {
+                let e = entries.get_or_insert_with(|| file.library_entries().into_iter()).next()?;
+                Some(f(VisitParams { file, location: None, module_name: &e.module_name, version: &e.version, package: &e.package, name: &e.name, extension: &e.extension }))
+            },
+        }
+    }
+}
+
+impl Iterator for Resources<'_> {
+    type Item = ResourceEntry;
+
+    fn next(&mut self) -> Option<ResourceEntry> {
+        if let ResourcesInner::Library { entries } = &mut self.inner {
+            let file = self.file;
+            return entries.get_or_insert_with(|| file.library_entries().into_iter()).next();
+        }
+        self.next_with(|params| params.to_entry())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.inner {
+            ResourcesInner::Native { image, index }     => (0, Some((image.table_length() - *index) as usize)),
+            ResourcesInner::Jmod { jmod, index }        => { let n = jmod.resources().len() - *index; (n, Some(n)) },
+            ResourcesInner::Library { entries: None }   => (0, None),
+            ResourcesInner::Library { entries: Some(entries) } => entries.size_hint(),
+        }
+    }
+}
+
+/// Join a resource's package ("java/lang"), name ("Object"), and extension ("class") into a name for
+/// [File::find_resource] ("java/lang/Object.class").  Resources without a package (e.g. "module-info.class") or
+/// extension don't get the '/' or '.' separators.
+/// 
+/// [File::find_resource]:  struct.File.html#method.find_resource
+fn resource_name(package: &CStr, name: &CStr, extension: &CStr) -> Result<CString> {
+    let package     = package   .to_str().map_err(|_| Error::NonUtf8 { what: "package", bytes: package.to_bytes().to_vec() })?;
+    let name        = name      .to_str().map_err(|_| Error::NonUtf8 { what: "name", bytes: name.to_bytes().to_vec() })?;
+    let extension   = extension .to_str().map_err(|_| Error::NonUtf8 { what: "extension", bytes: extension.to_bytes().to_vec() })?;
+    let mut path = String::with_capacity(package.len() + name.len() + extension.len() + 2);
+    if !package.is_empty()      { path.push_str(package); path.push('/'); }
+    path.push_str(name);
+    if !extension.is_empty()    { path.push('.'); path.push_str(extension); }
+    Ok(CString::new(path).unwrap()) // joined from CStrs, so no interior NULs
 }
 
 /// If [File::visit] should Cancel or Continue visiting more of the [File]
@@ -224,11 +973,6 @@ impl<'file> VisitParams<'file> {
     Continue,
 }
 
-struct VisitContext<'file, F: FnMut(VisitParams) -> VisitResult> {
-    file:   &'file File,
-    f:      F,
-}
-
 use ats::AssertThreadSafe;
 mod ats {
     /// Assert that an individual field is "thread safe" (Sync + Send).  To make this type sound, this
@@ -240,18 +984,3 @@ mod ats {
     impl<T> std::ops::Deref     for AssertThreadSafe<T> { fn deref    (&    self) -> &    Self::Target { &    self.0 } type Target = T; }
     impl<T> std::ops::DerefMut  for AssertThreadSafe<T> { fn deref_mut(&mut self) -> &mut Self::Target { &mut self.0 } }
 }
-
-fn ji2io(prefix: impl Display, err: impl Into<jlong>) -> Error {
-    const JIMAGE_NOT_FOUND      : jlong = sys::JIMAGE_NOT_FOUND as jlong;
-    const JIMAGE_BAD_MAGIC      : jlong = sys::JIMAGE_BAD_MAGIC as jlong;
-    const JIMAGE_BAD_VERSION    : jlong = sys::JIMAGE_BAD_VERSION as jlong;
-    const JIMAGE_CORRUPTED      : jlong = sys::JIMAGE_CORRUPTED as jlong;
-
-    match err.into() {
-        JIMAGE_NOT_FOUND    => Error::new(ErrorKind::NotFound,      format!("{}: JIMAGE_NOT_FOUND",     prefix)),
-        JIMAGE_BAD_MAGIC    => Error::new(ErrorKind::InvalidData,   format!("{}: JIMAGE_BAD_MAGIC",     prefix)),
-        JIMAGE_BAD_VERSION  => Error::new(ErrorKind::InvalidData,   format!("{}: JIMAGE_BAD_VERSION",   prefix)),
-        JIMAGE_CORRUPTED    => Error::new(ErrorKind::InvalidData,   format!("{}: JIMAGE_CORRUPTED",     prefix)),
-        other               => Error::new(ErrorKind::Other,         format!("{}: JIMAGE_??? ({})",      prefix, other)),
-    }
-}
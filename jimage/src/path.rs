@@ -0,0 +1,62 @@
+use crate::{Error, Result};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// A module-qualified resource path such as `/java.base/java/lang/Object.class`
+///
+/// This is the same form jrt paths and jimage locations use: a module name, followed by the resource's path within
+/// that module.  Parsing accepts both `/module/path` and `module/path`, formatting always produces `/module/path`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ResourcePath {
+    module: String,
+    path:   String,
+}
+
+impl ResourcePath {
+    /// Create a path from a module ("java.base") and a path within that module ("java/lang/Object.class")
+    pub fn new(module: impl Into<String>, path: impl Into<String>) -> Result<Self> {
+        let module  = module.into();
+        let path    = path.into();
+        if module.is_empty() || module.contains(['/', '\0']) {
+            return Err(Error::invalid_input(format!("ResourcePath::new({:?}, {:?}) failed: invalid module name", module, path)));
+        }
+        if path.is_empty() || path.starts_with('/') || path.ends_with('/') || path.contains('\0') {
+            return Err(Error::invalid_input(format!("ResourcePath::new({:?}, {:?}) failed: invalid resource path", module, path)));
+        }
+        Ok(Self { module, path })
+    }
+
+    /// Parse `"/java.base/java/lang/Object.class"` or `"java.base/java/lang/Object.class"`
+    pub fn parse(s: &str) -> Result<Self> {
+        let trimmed = s.strip_prefix('/').unwrap_or(s);
+        let slash = trimmed.find('/').ok_or_else(|| Error::invalid_input(format!("ResourcePath::parse({:?}) failed: expected \"/module/path\"", s)))?;
+        Self::new(&trimmed[..slash], &trimmed[slash+1..])
+    }
+
+    /// The module name (e.g. `"java.base"`)
+    pub fn module(&self) -> &str { &self.module }
+
+    /// The path within the module (e.g. `"java/lang/Object.class"`)
+    pub fn path(&self) -> &str { &self.path }
+
+    /// The package (e.g. `"java/lang"`), or `""` for resources at the root of a module such as `module-info.class`
+    pub fn package(&self) -> &str { self.path.rfind('/').map_or("", |i| &self.path[..i]) }
+
+    /// The file name (e.g. `"Object.class"`)
+    pub fn file_name(&self) -> &str { self.path.rfind('/').map_or(&self.path[..], |i| &self.path[i+1..]) }
+
+    /// The name without the extension (e.g. `"Object"`)
+    pub fn name(&self) -> &str { let f = self.file_name(); f.rfind('.').map_or(f, |i| &f[..i]) }
+
+    /// The file extension (e.g. `"class"`), or `""` if there is none
+    pub fn extension(&self) -> &str { let f = self.file_name(); f.rfind('.').map_or("", |i| &f[i+1..]) }
+}
+
+impl Display for ResourcePath {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result { write!(fmt, "/{}/{}", self.module, self.path) }
+}
+
+impl FromStr for ResourcePath {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> { Self::parse(s) }
+}
@@ -0,0 +1,229 @@
+use crate::image::Data;
+use crate::zip::{self, ZipEntry};
+use crate::{extract, jmod, Error, File, ResourcePath, Result};
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Anything resources can be enumerated, found, and read from by module-qualified [ResourcePath] - regardless of
+/// whether they're packaged as a jimage [File], a `.jmod` ([File::open_jmod]), a [Jar], or [ExplodedModules].
+///
+/// This is object safe, so module-path style resolvers can search a `Vec<Box<dyn ResourceSource>>`.
+///
+/// [ResourcePath]:     struct.ResourcePath.html
+/// [File]:             struct.File.html
+/// [File::open_jmod]:  struct.File.html#method.open_jmod
+/// [Jar]:              struct.Jar.html
+/// [ExplodedModules]:  struct.ExplodedModules.html
+pub trait ResourceSource {
+    /// Every resource, sorted by module, then path
+    fn resource_paths(&self) -> Result<Vec<ResourcePath>>;
+
+    /// Every module with at least one resource, in sorted order
+    fn module_names(&self) -> Result<Vec<String>> {
+        let modules = self.resource_paths()?.into_iter().map(|p| p.module().to_string()).collect::<BTreeSet<_>>();
+        Ok(modules.into_iter().collect())
+    }
+
+    /// Find a resource, returning its size in bytes.  Fails with [ErrorKind::NotFound] if there's no such resource.
+    ///
+    /// [ErrorKind::NotFound]:  https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.NotFound
+    fn resource_size(&self, path: &ResourcePath) -> Result<u64>;
+
+    /// Read the entire contents of a resource.  Fails with [ErrorKind::NotFound] if there's no such resource.
+    ///
+    /// [ErrorKind::NotFound]:  https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.NotFound
+    fn read_resource(&self, path: &ResourcePath) -> Result<Cow<'_, [u8]>>;
+}
+
+impl ResourceSource for File {
+    fn resource_paths(&self) -> Result<Vec<ResourcePath>> {
+        let mut paths = self.resources().map(|e| e.path()).collect::<Result<Vec<_>>>()?;
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn module_names(&self) -> Result<Vec<String>> { Ok(self.modules().map(String::from).collect()) }
+    fn resource_size(&self, path: &ResourcePath) -> Result<u64> { Ok(self.find_path(path)?.size()) }
+    fn read_resource(&self, path: &ResourcePath) -> Result<Cow<'_, [u8]>> { self.find_path(path)?.bytes() }
+}
+
+
+
+/// A directory of exploded modules, laid out as `<root>/<module>/<path>` - such as the output of
+/// [File::extract_to] or `jimage extract`, or a JDK build's `exploded-image/modules` directory.
+///
+/// Each subdirectory of the root is a module.  The directory is enumerated on demand, not cached.
+///
+/// [File::extract_to]: struct.File.html#method.extract_to
+#[derive(Clone, Debug)]
+pub struct ExplodedModules {
+    root:   PathBuf,
+}
+
+impl ExplodedModules {
+    /// Use the modules under `root`, which must be an existing directory
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        let metadata = std::fs::metadata(&root).map_err(|err| Error::from(err).with_path(root.as_path()))?;
+        if !metadata.is_dir() { return Err(Error::invalid_input(format!("ExplodedModules::open({:?}) failed: not a directory", root))); }
+        Ok(Self { root })
+    }
+
+    /// The directory containing the modules
+    pub fn root(&self) -> &Path { &self.root }
+
+    /// The file a resource would be at, if it exists - or `None` if the path would escape the module (e.g. via `..`)
+    pub fn file_path(&self, path: &ResourcePath) -> Option<PathBuf> { extract::destination(&self.root, path) }
+
+    fn file(&self, path: &ResourcePath) -> Result<PathBuf> {
+        let not_found = || Error::not_found(path.to_string(), "no such resource").with_path(self.root.as_path());
+        let file = self.file_path(path).ok_or_else(not_found)?;
+        if file.is_file() { Ok(file) } else { Err(not_found()) }
+    }
+}
+
+impl ResourceSource for ExplodedModules {
+    fn resource_paths(&self) -> Result<Vec<ResourcePath>> {
+        fn walk(module: &str, dir: &Path, prefix: &str, out: &mut Vec<ResourcePath>) -> Result<()> {
+            for entry in std::fs::read_dir(dir).map_err(|err| Error::from(err).with_path(dir))? {
+                let entry = entry.map_err(|err| Error::from(err).with_path(dir))?;
+                let name = entry.file_name().into_string().map_err(|name| Error::NonUtf8 { what: "file name", bytes: name.to_string_lossy().into_owned().into_bytes() })?;
+                let path = entry.path();
+                let resource = if prefix.is_empty() { name } else { format!("{}/{}", prefix, name) };
+                if path.is_dir() { walk(module, &path, &resource, out)?; } else { out.push(ResourcePath::new(module, resource)?); }
+            }
+            Ok(())
+        }
+
+        let mut paths = Vec::new();
+        for module in self.module_names()? {
+            walk(&module, &self.root.join(&module), "", &mut paths)?;
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn module_names(&self) -> Result<Vec<String>> {
+        let mut modules = Vec::new();
+        for entry in std::fs::read_dir(&self.root).map_err(|err| Error::from(err).with_path(self.root.as_path()))? {
+            let entry = entry.map_err(|err| Error::from(err).with_path(self.root.as_path()))?;
+            if !entry.path().is_dir() { continue; }
+            modules.push(entry.file_name().into_string().map_err(|name| Error::NonUtf8 { what: "module directory", bytes: name.to_string_lossy().into_owned().into_bytes() })?);
+        }
+        modules.sort();
+        Ok(modules)
+    }
+
+    fn resource_size(&self, path: &ResourcePath) -> Result<u64> {
+        let file = self.file(path)?;
+        Ok(std::fs::metadata(&file).map_err(|err| Error::from(err).with_path(file.as_path()))?.len())
+    }
+
+    fn read_resource(&self, path: &ResourcePath) -> Result<Cow<'_, [u8]>> {
+        let file = self.file(path)?;
+        Ok(Cow::Owned(std::fs::read(&file).map_err(|err| Error::from(err).with_path(file.as_path()))?))
+    }
+}
+
+
+
+/// A `.jar` file, whose entries are the resources of a single module
+///
+/// The module is named by the jar's `module-info.class` if it has one, or else - like an automatic module - by the
+/// `Automatic-Module-Name` of its manifest, or else by its file name (`foo-bar-1.2.jar` → `foo.bar`).  Directory
+/// entries are skipped, and `META-INF/versions/` entries of multi-release jars are exposed as-is.
+pub struct Jar {
+    path:       Option<PathBuf>, // None if parsed from memory
+    data:       Data,
+    module:     String,
+    entries:    HashMap<String, ZipEntry>,
+    paths:      Vec<ResourcePath>,
+}
+
+impl Jar {
+    /// Read and parse a `.jar` file
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).map_err(|err| Error::from(err).with_path(path))?;
+        let stem = path.file_name().and_then(|n| n.to_str()).map(|n| n.strip_suffix(".jar").unwrap_or(n)).unwrap_or("");
+        let mut jar = Self::parse(Data::Owned(data), automatic_module_name(stem)).map_err(|err| err.with_path(path))?;
+        jar.path = Some(path.to_path_buf());
+        Ok(jar)
+    }
+
+    /// Parse an in-memory `.jar` file.  As there's no file name to fall back on, `module_name` is used if the jar has
+    /// neither a `module-info.class` nor an `Automatic-Module-Name`.
+    pub fn from_bytes(bytes: Vec<u8>, module_name: &str) -> Result<Self> {
+        Self::parse(Data::Owned(bytes), Some(module_name.to_string()))
+    }
+
+    fn parse(data: Data, fallback: Option<String>) -> Result<Self> {
+        let entries = zip::read_entries(&data)?.into_iter().filter(|e| !e.name.ends_with('/')).map(|e| (e.name.clone(), e)).collect::<HashMap<_, _>>();
+        let read = |name: &str| entries.get(name).map(|e| zip::read(&data, e)).transpose();
+
+        let module = match read("module-info.class")? {
+            Some(class) => Some(jmod::module_name(&class).ok_or_else(|| Error::corrupted("couldn't read the module name from module-info.class").with_resource("module-info.class"))?),
+            None => read("META-INF/MANIFEST.MF")?.and_then(|manifest| manifest_attribute(&manifest, "Automatic-Module-Name")),
+        };
+        let module = module.or(fallback).filter(|m| !m.is_empty())
+            .ok_or_else(|| Error::invalid_input("jar has no module-info.class or Automatic-Module-Name, and no usable file name to derive a module name from"))?;
+
+        let mut paths = entries.keys().map(|name| ResourcePath::new(module.as_str(), name.as_str())).collect::<Result<Vec<_>>>()?;
+        paths.sort();
+        Ok(Self { path: None, data, module, entries, paths })
+    }
+
+    /// The path this jar was opened from, or `None` if it was parsed from memory with [Jar::from_bytes]
+    ///
+    /// [Jar::from_bytes]:  #method.from_bytes
+    pub fn path(&self) -> Option<&Path> { self.path.as_deref() }
+
+    /// The name of the module (e.g. `"org.example.library"`)
+    pub fn module_name(&self) -> &str { &self.module }
+
+    fn entry(&self, path: &ResourcePath) -> Result<&ZipEntry> {
+        let entry = if path.module() == self.module { self.entries.get(path.path()) } else { None };
+        entry.ok_or_else(|| Error::not_found(path.to_string(), "no such resource").with_path(self.path()))
+    }
+}
+
+impl ResourceSource for Jar {
+    fn resource_paths(&self) -> Result<Vec<ResourcePath>> { Ok(self.paths.clone()) }
+    fn module_names(&self) -> Result<Vec<String>> { Ok(vec![self.module.clone()]) }
+    fn resource_size(&self, path: &ResourcePath) -> Result<u64> { Ok(self.entry(path)?.size) }
+    fn read_resource(&self, path: &ResourcePath) -> Result<Cow<'_, [u8]>> {
+        zip::read(&self.data, self.entry(path)?).map_err(|err| err.with_path(self.path()))
+    }
+}
+
+/// The value of `name` in a manifest's main section, ignoring continuation lines
+fn manifest_attribute(manifest: &[u8], name: &str) -> Option<String> {
+    let manifest = String::from_utf8_lossy(manifest);
+    manifest.lines().take_while(|line| !line.is_empty()).find_map(|line| {
+        let (key, value) = line.split_at(line.find(':')?);
+        if key.eq_ignore_ascii_case(name) { Some(value[1..].trim().to_string()) } else { None }
+    })
+}
+
+/// Derive a module name from a jar's file name (without `.jar`), like `java.lang.module.ModuleFinder` does for
+/// automatic modules: drop any `-<digit>...` version suffix, replace non-alphanumerics with dots, then collapse
+/// repeated dots and trim leading & trailing ones.
+fn automatic_module_name(stem: &str) -> Option<String> {
+    let bytes = stem.as_bytes();
+    let version = (0..bytes.len()).find(|&i| {
+        bytes[i] == b'-' && bytes.get(i+1).is_some_and(u8::is_ascii_digit) && {
+            let digits = bytes[i+1..].iter().position(|b| !b.is_ascii_digit()).map_or(bytes.len(), |n| i+1+n);
+            digits == bytes.len() || bytes[digits] == b'.'
+        }
+    });
+    let name = &stem[..version.unwrap_or(stem.len())];
+    let mut module = String::with_capacity(name.len());
+    for c in name.chars() {
+        let c = if c.is_ascii_alphanumeric() { c } else { '.' };
+        if c == '.' && (module.is_empty() || module.ends_with('.')) { continue; }
+        module.push(c);
+    }
+    while module.ends_with('.') { module.pop(); }
+    if module.is_empty() { None } else { Some(module) }
+}
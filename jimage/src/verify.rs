@@ -0,0 +1,106 @@
+use crate::compression;
+use crate::image::{self, Image};
+use crate::jmod::Jmod;
+use std::fmt::{self, Display, Formatter};
+
+/// The results of [File::verify]
+///
+/// [File::verify]:     struct.File.html#method.verify
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// How many slots of the offsets table were checked
+    pub locations:  u32,
+    /// How many named locations (resources, plus the `/modules` and `/packages` directories) were checked
+    pub resources:  u32,
+    /// Every problem found, in slot order
+    pub problems:   Vec<Problem>,
+}
+
+impl VerifyReport {
+    /// If no problems were found
+    pub fn is_ok(&self) -> bool { self.problems.is_empty() }
+}
+
+/// A single integrity problem found by [File::verify]
+///
+/// [File::verify]:     struct.File.html#method.verify
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Problem {
+    /// The `index`th slot's location attributes lie outside the location attributes section, or fail to decode
+    BadLocation { index: u32 },
+
+    /// The `index`th slot's module, parent, base, or extension lie outside the strings table
+    BadName { index: u32 },
+
+    /// Hashing the resource's name doesn't lead back to its slot, so lookups by name won't find it
+    HashMismatch { index: u32, resource: String, found: Option<u32> },
+
+    /// The resource's content lies outside the file
+    ContentOutOfBounds { index: u32, resource: String, offset: u64, size: u64 },
+
+    /// The resource's content failed to decompress, or decompressed to a size other than the one declared
+    Decompression { index: u32, resource: String, message: String },
+}
+
+impl Display for Problem {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            Problem::BadLocation { index }                              => write!(fmt, "slot {}: location attributes are out of bounds or undecodable", index),
+            Problem::BadName { index }                                  => write!(fmt, "slot {}: name strings are out of bounds", index),
+            Problem::HashMismatch { index, resource, found: None }      => write!(fmt, "slot {}: {:?} doesn't hash to any slot", index, resource),
+            Problem::HashMismatch { index, resource, found: Some(f) }   => write!(fmt, "slot {}: {:?} hashes to slot {} instead", index, resource, f),
+            Problem::ContentOutOfBounds { index, resource, offset, size } => write!(fmt, "slot {}: {:?} content ({} bytes at offset {}) is out of bounds", index, resource, size, offset),
+            Problem::Decompression { index, resource, message }         => write!(fmt, "slot {}: {:?} failed to decompress: {}", index, resource, message),
+        }
+    }
+}
+
+pub(crate) fn verify(image: &Image) -> VerifyReport {
+    let mut report = VerifyReport { locations: image.table_length(), ..VerifyReport::default() };
+    for index in 0..image.table_length() {
+        let location = match image.location(index) {
+            Some(location) => location,
+            None => { report.problems.push(Problem::BadLocation { index }); continue; },
+        };
+        let name = match image.full_name(&location) {
+            Some(name) => name,
+            None => { report.problems.push(Problem::BadName { index }); continue; },
+        };
+        if name.is_empty() { continue; } // the reserved empty location unused slots point at
+        report.resources += 1;
+        let resource = String::from_utf8_lossy(&name).into_owned();
+
+        let found = image.find_index(&name);
+        if found != Some(index) {
+            report.problems.push(Problem::HashMismatch { index, resource: resource.clone(), found });
+        }
+
+        let content = match image.content(&location) {
+            Some(content) => content,
+            None => {
+                let size = match location.get(image::ATTRIBUTE_COMPRESSED) { 0 => location.get(image::ATTRIBUTE_UNCOMPRESSED), size => size };
+                report.problems.push(Problem::ContentOutOfBounds { index, resource, offset: location.get(image::ATTRIBUTE_OFFSET), size });
+                continue;
+            },
+        };
+
+        if location.get(image::ATTRIBUTE_COMPRESSED) != 0 {
+            if let Err(err) = compression::decompress_with_byte_order(content, image.strings(), image.header().byte_order, location.get(image::ATTRIBUTE_UNCOMPRESSED)) {
+                report.problems.push(Problem::Decompression { index, resource, message: err.to_string() });
+            }
+        }
+    }
+    report
+}
+
+pub(crate) fn verify_jmod(jmod: &Jmod) -> VerifyReport {
+    let mut report = VerifyReport { locations: jmod.entries().len() as u32, resources: jmod.entries().len() as u32, ..VerifyReport::default() };
+    for (index, entry) in jmod.entries().iter().enumerate() {
+        if let Err(err) = jmod.read(entry) {
+            let resource = format!("{}/{}", entry.section().directory(), entry.name());
+            report.problems.push(Problem::Decompression { index: index as u32, resource, message: err.to_string() });
+        }
+    }
+    report
+}
@@ -0,0 +1,284 @@
+//! Writing jimage files, such as trimmed `lib/modules` images, without `jlink`.
+//!
+//! [ImageBuilder] collects `(module, path, bytes)` resources and lays them out the way jlink does: a [Header], a
+//! perfect hash redirect table, an offsets table, location attributes, a strings table, then resource contents.  The
+//! result can be opened by [File::open_native](../struct.File.html#method.open_native) and, if written in the host's
+//! byte order, by libjimage.
+//!
+//! Like jlink, the image also contains a `/packages/<package>` entry per package, mapping it to its module(s) for
+//! [File::package_to_module](../struct.File.html#method.package_to_module).  The `/modules/...` directory entries
+//! jlink writes for the `jrt:/` filesystem are not written.
+//!
+//!  References:
+//! https://github.com/AdoptOpenJDK/openjdk-jdk13u/blob/f3283b6e2d7676423a23c372754ceef7d2ee731f/src/jdk.jlink/share/classes/jdk/tools/jlink/internal/BasicImageWriter.java
+//! https://github.com/AdoptOpenJDK/openjdk-jdk13u/blob/f3283b6e2d7676423a23c372754ceef7d2ee731f/src/jdk.jlink/share/classes/jdk/tools/jlink/internal/PerfectHashBuilder.java
+//!
+//! [ImageBuilder]:     struct.ImageBuilder.html
+//! [Header]:           ../struct.Header.html
+
+use crate::compression::{ResourceHeader, RESOURCE_HEADER_MAGIC};
+use crate::image::{self, hash_code, HASH_MULTIPLIER, IMAGE_MAGIC, MAJOR_VERSION, MINOR_VERSION};
+use crate::{ByteOrder, Error, Header, Result};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::convert::TryFrom;
+use std::path::Path;
+
+/// Builds a jimage file from `(module, path, bytes)` resources
+///
+/// ```
+/// let mut builder = jimage::write::ImageBuilder::new();
+/// builder.add("java.base", "java/lang/Object.class", b"\xCA\xFE\xBA\xBE...".to_vec())?;
+/// let file = jimage::File::from_bytes(builder.to_bytes()?)?;
+/// assert_eq!(file.find("/java.base/java/lang/Object.class")?.size(), 7);
+/// # Ok::<(), jimage::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct ImageBuilder {
+    byte_order: ByteOrder,
+    resources:  Vec<Entry>,
+    names:      HashSet<String>,
+    strings:    Strings,
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+    module:             String,
+    path:               String,
+    content:            Vec<u8>,
+    uncompressed_size:  Option<u64>, // Some if content is compressed
+}
+
+impl Default for ImageBuilder {
+    fn default() -> Self { Self::new() }
+}
+
+impl ImageBuilder {
+    /// An empty image in the host's byte order
+    pub fn new() -> Self {
+        Self { byte_order: ByteOrder::NATIVE, resources: Vec::new(), names: HashSet::new(), strings: Strings::new() }
+    }
+
+    /// The byte order the image (and [ImageBuilder::compress]ed resource headers) will be written in
+    ///
+    /// [ImageBuilder::compress]:   #method.compress
+    pub fn byte_order(&self) -> ByteOrder { self.byte_order }
+
+    /// Write the image in another byte order - e.g. to build images for s390x on x86_64.
+    ///
+    /// Resource headers from [ImageBuilder::compress] are written in the byte order current at the time, so call
+    /// this first.  libjimage only opens images in the host's byte order.
+    ///
+    /// [ImageBuilder::compress]:   #method.compress
+    pub fn set_byte_order(&mut self, byte_order: ByteOrder) -> &mut Self { self.byte_order = byte_order; self }
+
+    /// Add a resource, such as (`"java.base"`, `"java/lang/Object.class"`, bytes)
+    ///
+    /// Fails with [Error::InvalidInput] if the module or path is malformed, or the resource was already added.
+    ///
+    /// [Error::InvalidInput]:  ../enum.Error.html#variant.InvalidInput
+    pub fn add(&mut self, module: &str, path: &str, bytes: impl Into<Vec<u8>>) -> Result<&mut Self> {
+        self.push(module, path, bytes.into(), None)
+    }
+
+    /// Add a resource whose content is already compressed, from `uncompressed_size` bytes, with
+    /// [ImageBuilder::compress] or [ImageBuilder::zip].
+    ///
+    /// [ImageBuilder::compress]:   #method.compress
+    /// [ImageBuilder::zip]:        #method.zip
+    pub fn add_compressed(&mut self, module: &str, path: &str, stored: impl Into<Vec<u8>>, uncompressed_size: u64) -> Result<&mut Self> {
+        self.push(module, path, stored.into(), Some(uncompressed_size))
+    }
+
+    /// Prefix a decompressor's `payload` with a [ResourceHeader](../compression/struct.ResourceHeader.html) naming
+    /// `decompressor` (e.g. `"zip"` or `"compact-cp"`).  Layers may be nested by compressing the result again.
+    pub fn compress(&mut self, decompressor: &str, payload: &[u8], uncompressed_size: u64) -> Vec<u8> {
+        let header = ResourceHeader {
+            magic:                      RESOURCE_HEADER_MAGIC,
+            size:                       payload.len() as u64,
+            uncompressed_size,
+            decompressor_name_offset:   self.string(decompressor),
+            decompressor_config_offset: u32::MAX, // no configuration, like jlink
+            is_terminal:                true,
+        };
+        let mut stored = header.to_bytes(self.byte_order).to_vec();
+        stored.extend_from_slice(payload);
+        stored
+    }
+
+    /// Compress `data` with the `"zip"` decompressor (zlib), for [ImageBuilder::add_compressed].
+    ///
+    /// [ImageBuilder::add_compressed]: #method.add_compressed
+    pub fn zip(&mut self, data: &[u8]) -> Vec<u8> {
+        let payload = miniz_oxide::deflate::compress_to_vec_zlib(data, 6);
+        self.compress("zip", &payload, data.len() as u64)
+    }
+
+    /// Intern a string into the strings table, returning its offset - e.g. for `"compact-cp"` payloads.
+    pub fn string(&mut self, s: &str) -> u32 { self.strings.add(s) }
+
+    /// Serialize the image
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        // "/packages/java.lang" entries: (is_empty, module name offset) pairs, one per module with the package
+        let mut packages = BTreeMap::<String, BTreeSet<&str>>::new();
+        for entry in self.resources.iter() {
+            if let Some(slash) = entry.path.rfind('/') {
+                packages.entry(entry.path[..slash].replace('/', ".")).or_default().insert(&entry.module);
+            }
+        }
+
+        let mut strings = self.strings.clone();
+        let mut entries = self.resources.iter().map(|e| (format!("/{}/{}", e.module, e.path), split(&e.module, &e.path), &e.content[..], e.uncompressed_size)).collect::<Vec<_>>();
+        let package_contents = packages.iter().map(|(package, modules)| {
+            let mut content = Vec::with_capacity(8 * modules.len());
+            for module in modules {
+                content.extend_from_slice(&self.byte_order.u4_bytes(0));
+                content.extend_from_slice(&self.byte_order.u4_bytes(strings.add(module)));
+            }
+            (package, content)
+        }).collect::<Vec<_>>();
+        for (package, content) in package_contents.iter() {
+            entries.push((format!("/packages/{}", package), ["packages", "", package, ""], &content[..], None));
+        }
+
+        let mut locations = vec![image::ATTRIBUTE_END as u8]; // offset 0 is an empty location, used by unoccupied slots
+        let mut location_offsets = Vec::with_capacity(entries.len());
+        let mut content_offset = 0u64;
+        for (_, names, content, uncompressed_size) in entries.iter() {
+            location_offsets.push(u32::try_from(locations.len()).map_err(|_| too_big("location attributes"))?);
+            let size = content.len() as u64;
+            let attributes = [
+                (image::ATTRIBUTE_MODULE,       u64::from(strings.add(names[0]))),
+                (image::ATTRIBUTE_PARENT,       u64::from(strings.add(names[1]))),
+                (image::ATTRIBUTE_BASE,         u64::from(strings.add(names[2]))),
+                (image::ATTRIBUTE_EXTENSION,    u64::from(strings.add(names[3]))),
+                (image::ATTRIBUTE_OFFSET,       content_offset),
+                (image::ATTRIBUTE_COMPRESSED,   if uncompressed_size.is_some() { size } else { 0 }),
+                (image::ATTRIBUTE_UNCOMPRESSED, uncompressed_size.unwrap_or(size)),
+            ];
+            for &(kind, value) in attributes.iter() {
+                if value == 0 { continue; } // absent attributes read as 0
+                let n = (63 - value.leading_zeros() as usize) / 8; // value bytes - 1
+                locations.push(((kind << 3) | n) as u8);
+                for i in (0..=n).rev() { locations.push((value >> (i * 8)) as u8); }
+            }
+            locations.push(image::ATTRIBUTE_END as u8);
+            content_offset += size;
+        }
+
+        let names = entries.iter().map(|(name, ..)| name.as_bytes()).collect::<Vec<_>>();
+        let (redirect, slots) = perfect_hash(&names);
+
+        let header = Header {
+            magic:          IMAGE_MAGIC,
+            major_version:  MAJOR_VERSION as u16,
+            minor_version:  MINOR_VERSION as u16,
+            flags:          0,
+            resource_count: u32::try_from(entries.len()).map_err(|_| too_big("resource count"))?,
+            table_length:   u32::try_from(redirect.len()).map_err(|_| too_big("resource count"))?,
+            locations_size: u32::try_from(locations.len()).map_err(|_| too_big("location attributes"))?,
+            strings_size:   u32::try_from(strings.bytes.len()).map_err(|_| too_big("strings table"))?,
+            byte_order:     self.byte_order,
+        };
+
+        let mut out = Vec::with_capacity(usize::try_from(header.index_size() + content_offset).unwrap_or(0));
+        out.extend_from_slice(&header.to_bytes());
+        for &r in redirect.iter() { out.extend_from_slice(&self.byte_order.u4_bytes(r as u32)); }
+        for slot in slots.iter() { out.extend_from_slice(&self.byte_order.u4_bytes(slot.map_or(0, |e| location_offsets[e]))); }
+        out.extend_from_slice(&locations);
+        out.extend_from_slice(&strings.bytes);
+        for (_, _, content, _) in entries.iter() { out.extend_from_slice(content); }
+        Ok(out)
+    }
+
+    /// Serialize the image to a file, such as `lib/modules`
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let bytes = self.to_bytes()?;
+        std::fs::write(path, bytes).map_err(|err| Error::from(err).with_path(path))
+    }
+
+    fn push(&mut self, module: &str, path: &str, content: Vec<u8>, uncompressed_size: Option<u64>) -> Result<&mut Self> {
+        let invalid = |why: &str| Error::invalid_input(format!("can't add ({:?}, {:?}): {}", module, path, why));
+        if module.is_empty() || module.contains('/') || module.contains('\0') { return Err(invalid("invalid module name")); }
+        if module == "modules" || module == "packages" { return Err(invalid("module name is reserved for jimage directories")); }
+        if path.is_empty() || path.contains('\0') || path.split('/').any(|c| c.is_empty()) { return Err(invalid("invalid resource path")); }
+        if !self.names.insert(format!("/{}/{}", module, path)) { return Err(invalid("resource already added")); }
+        self.resources.push(Entry { module: module.into(), path: path.into(), content, uncompressed_size });
+        Ok(self)
+    }
+}
+
+fn too_big(what: &str) -> Error { Error::invalid_input(format!("image is too large for jimage: {} exceeds 4 GiB", what)) }
+
+/// Split a module + path into the (module, parent, base, extension) strings of its location, like jlink's
+/// `ImageLocationWriter`.
+fn split<'a>(module: &'a str, path: &'a str) -> [&'a str; 4] {
+    let (parent, file) = match path.rfind('/') { Some(i) => (&path[..i], &path[i+1..]), None => ("", path) };
+    let (base, extension) = match file.rfind('.') { Some(i) => (&file[..i], &file[i+1..]), None => (file, "") };
+    [module, parent, base, extension]
+}
+
+#[derive(Clone, Debug)]
+struct Strings {
+    bytes:      Vec<u8>,
+    offsets:    HashMap<String, u32>,
+}
+
+impl Strings {
+    fn new() -> Self {
+        let mut offsets = HashMap::new();
+        offsets.insert(String::new(), 0);
+        Self { bytes: vec![0], offsets }
+    }
+
+    fn add(&mut self, s: &str) -> u32 {
+        if let Some(&offset) = self.offsets.get(s) { return offset; }
+        let offset = self.bytes.len() as u32;
+        self.bytes.extend_from_slice(s.as_bytes());
+        self.bytes.push(0);
+        self.offsets.insert(s.to_string(), offset);
+        offset
+    }
+}
+
+/// Build the (redirect table, slot → entry index) pair the same way jlink's `PerfectHashBuilder` does, growing the
+/// table whenever some bucket can't find a collision-free seed.
+fn perfect_hash(names: &[&[u8]]) -> (Vec<i32>, Vec<Option<usize>>) {
+    (names.len().max(1)..).find_map(|count| try_perfect_hash(names, count)).unwrap()
+}
+
+fn try_perfect_hash(names: &[&[u8]], count: usize) -> Option<(Vec<i32>, Vec<Option<usize>>)> {
+    let mut buckets = vec![Vec::new(); count];
+    for (i, name) in names.iter().enumerate() { buckets[hash_code(name, HASH_MULTIPLIER) as usize % count].push(i); }
+    let mut order = (0..count).collect::<Vec<_>>();
+    order.sort_by_key(|&b| std::cmp::Reverse(buckets[b].len()));
+
+    let mut redirect = vec![0i32; count];
+    let mut slots = vec![None; count];
+    let mut next_free = 0; // single entry buckets come last, and fill the remaining slots in order
+    for b in order {
+        let bucket = &buckets[b];
+        match bucket.len() {
+            0 => {},
+            1 => {
+                while slots[next_free].is_some() { next_free += 1; }
+                slots[next_free] = Some(bucket[0]);
+                redirect[b] = -1 - next_free as i32;
+            },
+            _ => {
+                let seed = (1..0x1000).find(|&seed| {
+                    let mut taken = Vec::with_capacity(bucket.len());
+                    bucket.iter().all(|&e| {
+                        let slot = hash_code(names[e], seed) as usize % count;
+                        let free = slots[slot].is_none() && !taken.contains(&slot);
+                        taken.push(slot);
+                        free
+                    })
+                })?;
+                for &e in bucket { slots[hash_code(names[e], seed) as usize % count] = Some(e); }
+                redirect[b] = seed;
+            },
+        }
+    }
+    Some((redirect, slots))
+}
@@ -0,0 +1,216 @@
+//! A minimal zip reader and writer: deflated or stored entries, no zip64, no encryption.  Enough for `.jmod` files.
+//!
+//!  References:
+//! https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT
+
+use crate::{Error, Result};
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::io::Write;
+use std::ops::Range;
+
+const LOCAL_FILE_HEADER    : u32 = 0x04034b50;
+const CENTRAL_FILE_HEADER  : u32 = 0x02014b50;
+const END_OF_CENTRAL_DIR   : u32 = 0x06054b50;
+
+const VERSION       : u16 = 20; // 2.0: deflate
+const FLAG_UTF8     : u16 = 1 << 11;
+const STORED        : u16 = 0;
+const DEFLATED      : u16 = 8;
+const DOS_DATE      : u16 = (1 << 5) | 1; // 1980-01-01, so identical inputs produce identical zips
+const DOS_TIME      : u16 = 0;
+
+/// An entry of the zip's central directory, as read by [read_entries]
+#[derive(Clone, Debug)]
+pub(crate) struct ZipEntry {
+    pub name:       String,
+    pub method:     u16,
+    pub crc:        u32,
+    pub size:       u64,
+    /// The entry's stored (possibly compressed) bytes, relative to the start of the zip
+    pub data:       Range<usize>,
+}
+
+/// Read the central directory of `zip`, and locate each entry's stored bytes via its local header.
+///
+/// Like `java.util.zip.ZipFile`, offsets are relative to where the zip's central directory says the zip starts, so
+/// `zip` may have leading bytes (such as a jmod's `"JM"` header) that the zip's offsets don't account for.
+pub(crate) fn read_entries(zip: &[u8]) -> Result<Vec<ZipEntry>> {
+    let corrupted = |message: &str| Error::corrupted(format!("zip {}", message));
+    let u16_at = |o: usize| zip.get(o..o.checked_add(2)?).map(|b| u16::from_le_bytes([b[0], b[1]]));
+    let u32_at = |o: usize| zip.get(o..o.checked_add(4)?).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+    let add = |a: usize, b: usize, what: &str| a.checked_add(b).ok_or_else(|| corrupted(&format!("{} is out of bounds", what)));
+
+    // The end of central directory record is 22 bytes, followed by a comment of up to 64 KiB
+    let eocd = (zip.len().saturating_sub(22 + 0xFFFF) ..= zip.len().saturating_sub(22)).rev()
+        .find(|&o| u32_at(o) == Some(END_OF_CENTRAL_DIR))
+        .ok_or_else(|| corrupted("end of central directory not found"))?;
+    let count   = u16_at(eocd + 10).unwrap();
+    let size    = u32_at(eocd + 12).unwrap();
+    let offset  = u32_at(eocd + 16).unwrap();
+    if count == 0xFFFF || size == 0xFFFF_FFFF || offset == 0xFFFF_FFFF {
+        return Err(Error::Unsupported { message: "zip64 archives aren't supported".into() });
+    }
+    let base = eocd.checked_sub(size as usize).and_then(|cd| cd.checked_sub(offset as usize)).ok_or_else(|| corrupted("central directory is out of bounds"))?;
+
+    let mut entries = Vec::with_capacity(count.into());
+    let mut central = add(base, offset as usize, "central directory")?;
+    for _ in 0..count {
+        let header = zip.get(central .. add(central, 46, "central directory entry")?).filter(|h| h[..4] == CENTRAL_FILE_HEADER.to_le_bytes()).ok_or_else(|| corrupted("central directory entry is missing or corrupt"))?;
+        let u16_in = |o: usize| u16::from_le_bytes([header[o], header[o+1]]);
+        let u32_in = |o: usize| u32::from_le_bytes([header[o], header[o+1], header[o+2], header[o+3]]);
+        let (flags, method, crc, compressed, uncompressed) = (u16_in(8), u16_in(10), u32_in(16), u32_in(20), u32_in(24));
+        let (name_len, extra_len, comment_len, local) = (usize::from(u16_in(28)), usize::from(u16_in(30)), usize::from(u16_in(32)), u32_in(42));
+        let name_start = central + 46; // checked by the header read above
+        let name = zip.get(name_start .. add(name_start, name_len, "entry name")?).ok_or_else(|| corrupted("entry name is out of bounds"))?;
+        let name = String::from_utf8(name.to_vec()).map_err(|err| Error::NonUtf8 { what: "zip entry name", bytes: err.into_bytes() })?;
+        if flags & 1 != 0 { return Err(Error::Unsupported { message: format!("zip entry {:?} is encrypted", name) }); }
+
+        // The local header's name and extra field lengths can differ from the central directory's
+        let local = add(base, local as usize, "local header")?;
+        let (local_name_len, local_extra_len) = match (u32_at(local), local.checked_add(26).and_then(u16_at), local.checked_add(28).and_then(u16_at)) {
+            (Some(LOCAL_FILE_HEADER), Some(n), Some(e)) => (usize::from(n), usize::from(e)),
+            _ => return Err(corrupted(&format!("local header of {:?} is missing or corrupt", name))),
+        };
+        let out_of_bounds = || corrupted(&format!("entry {:?} is out of bounds", name));
+        let start = local.checked_add(30 + local_name_len + local_extra_len).ok_or_else(out_of_bounds)?;
+        let data = start .. start.checked_add(compressed as usize).ok_or_else(out_of_bounds)?;
+        if data.end > zip.len() { return Err(out_of_bounds()); }
+
+        entries.push(ZipEntry { name, method, crc, size: uncompressed.into(), data });
+        central = add(name_start, name_len + extra_len + comment_len, "central directory entry")?;
+    }
+    Ok(entries)
+}
+
+/// Read and, if deflated, inflate an entry's contents, checking its size and CRC
+pub(crate) fn read<'z>(zip: &'z [u8], entry: &ZipEntry) -> Result<Cow<'z, [u8]>> {
+    let corrupted = |message: String| Error::corrupted(message).with_resource(&entry.name);
+    let stored = &zip[entry.data.clone()];
+    let data = match entry.method {
+        STORED      => Cow::Borrowed(stored),
+        DEFLATED    => {
+            let limit = usize::try_from(entry.size).map_err(|_| corrupted(format!("{} bytes is too large to read into memory", entry.size)))?;
+            Cow::Owned(miniz_oxide::inflate::decompress_to_vec_with_limit(stored, limit).map_err(|err| corrupted(format!("failed to inflate: {:?}", err.status)))?)
+        },
+        other       => return Err(Error::Unsupported { message: format!("zip entry {:?} uses unsupported compression method {}", entry.name, other) }),
+    };
+    if data.len() as u64 != entry.size { return Err(corrupted(format!("expected {} bytes, got {}", entry.size, data.len()))); }
+    if crc32(&data) != entry.crc { return Err(corrupted("CRC mismatch".into())); }
+    Ok(data)
+}
+
+struct CentralEntry {
+    name:       String,
+    method:     u16,
+    crc:        u32,
+    compressed: u32,
+    size:       u32,
+    offset:     u32,
+}
+
+/// Writes entries as they're added, then the central directory on [ZipWriter::finish].  Offsets are relative to
+/// where the writer started, as `java.util.zip.ZipOutputStream`'s are, so the zip can follow a prefix like a jmod's
+/// `"JM"` header.
+pub(crate) struct ZipWriter<W: Write> {
+    out:        W,
+    position:   u64,
+    entries:    Vec<CentralEntry>,
+}
+
+impl<W: Write> ZipWriter<W> {
+    pub fn new(out: W) -> Self { Self { out, position: 0, entries: Vec::new() } }
+
+    pub fn add(&mut self, name: &str, data: &[u8]) -> Result<()> {
+        let too_large = || Error::Unsupported { message: format!("zip entry {:?} is too large: zip64 isn't supported", name) };
+        let deflated = miniz_oxide::deflate::compress_to_vec(data, 6);
+        let (method, stored) = if deflated.len() < data.len() { (DEFLATED, &deflated[..]) } else { (STORED, data) };
+        let entry = CentralEntry {
+            name:       name.into(),
+            method,
+            crc:        crc32(data),
+            compressed: u32::try_from(stored.len()).map_err(|_| too_large())?,
+            size:       u32::try_from(data.len()).map_err(|_| too_large())?,
+            offset:     u32::try_from(self.position).map_err(|_| too_large())?,
+        };
+
+        let mut header = Vec::with_capacity(30 + name.len());
+        header.extend_from_slice(&LOCAL_FILE_HEADER.to_le_bytes());
+        header.extend_from_slice(&VERSION.to_le_bytes());
+        header.extend_from_slice(&FLAG_UTF8.to_le_bytes());
+        header.extend_from_slice(&entry.method.to_le_bytes());
+        header.extend_from_slice(&DOS_TIME.to_le_bytes());
+        header.extend_from_slice(&DOS_DATE.to_le_bytes());
+        header.extend_from_slice(&entry.crc.to_le_bytes());
+        header.extend_from_slice(&entry.compressed.to_le_bytes());
+        header.extend_from_slice(&entry.size.to_le_bytes());
+        header.extend_from_slice(&u16::try_from(name.len()).map_err(|_| too_large())?.to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes()); // extra field length
+        header.extend_from_slice(name.as_bytes());
+        self.write(&header)?;
+        self.write(stored)?;
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<W> {
+        let too_many = || Error::Unsupported { message: "zip is too large: zip64 isn't supported".into() };
+        let start = self.position;
+        let mut directory = Vec::new();
+        for entry in self.entries.iter() {
+            directory.extend_from_slice(&CENTRAL_FILE_HEADER.to_le_bytes());
+            directory.extend_from_slice(&VERSION.to_le_bytes()); // version made by
+            directory.extend_from_slice(&VERSION.to_le_bytes()); // version needed to extract
+            directory.extend_from_slice(&FLAG_UTF8.to_le_bytes());
+            directory.extend_from_slice(&entry.method.to_le_bytes());
+            directory.extend_from_slice(&DOS_TIME.to_le_bytes());
+            directory.extend_from_slice(&DOS_DATE.to_le_bytes());
+            directory.extend_from_slice(&entry.crc.to_le_bytes());
+            directory.extend_from_slice(&entry.compressed.to_le_bytes());
+            directory.extend_from_slice(&entry.size.to_le_bytes());
+            directory.extend_from_slice(&(entry.name.len() as u16).to_le_bytes()); // checked by add
+            directory.extend_from_slice(&[0; 12]); // extra & comment lengths, disk number, internal & external attributes
+            directory.extend_from_slice(&entry.offset.to_le_bytes());
+            directory.extend_from_slice(entry.name.as_bytes());
+        }
+        let count = u16::try_from(self.entries.len()).map_err(|_| too_many())?;
+        let size = u32::try_from(directory.len()).map_err(|_| too_many())?;
+        let start = u32::try_from(start).map_err(|_| too_many())?;
+        directory.extend_from_slice(&END_OF_CENTRAL_DIR.to_le_bytes());
+        directory.extend_from_slice(&[0; 4]); // disk numbers
+        directory.extend_from_slice(&count.to_le_bytes()); // entries on this disk
+        directory.extend_from_slice(&count.to_le_bytes()); // entries total
+        directory.extend_from_slice(&size.to_le_bytes());
+        directory.extend_from_slice(&start.to_le_bytes());
+        directory.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        self.write(&directory)?;
+        Ok(self.out)
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<()> {
+        self.out.write_all(bytes)?;
+        self.position += bytes.len() as u64;
+        Ok(())
+    }
+}
+
+/// CRC-32 (IEEE 802.3), as zip uses
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, &b| CRC32_TABLE[((crc ^ u32::from(b)) & 0xFF) as usize] ^ (crc >> 8))
+}
+
+const CRC32_TABLE : [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { 0xEDB88320 ^ (crc >> 1) } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
@@ -0,0 +1,48 @@
+mod common;
+
+use jimage::ByteOrder;
+
+fn fixture(swapped: bool) -> common::Fixture {
+    let mut f = common::Fixture::small();
+    if swapped { f.swap_byte_order(); }
+    let zipped = f.zip(b"compressed text, compressed text");
+    f.add_compressed("/java.base/zipped.txt", zipped, 32);
+    let class = common::class_bytes("java/lang/Compact");
+    let compact = f.compress("compact-cp", &class, class.len() as u64);
+    let both = f.zip(&compact);
+    f.add_compressed("/java.base/java/lang/Compact.class", both, class.len() as u64);
+    f
+}
+
+fn contents(file: &jimage::File) -> Vec<(jimage::ResourceEntry, Vec<u8>)> {
+    file.resources().map(|e| { let bytes = e.resource(file).unwrap().read_to_vec().unwrap(); (e, bytes) }).collect()
+}
+
+#[test] fn swapped_byte_order() {
+    let native  = jimage::File::open_native(fixture(false).write("byte_order_native.jimage")).unwrap();
+    let swapped = jimage::File::open_mmap(fixture(true).write("byte_order_swapped.jimage")).unwrap();
+    assert_eq!(native.header().unwrap().byte_order, ByteOrder::NATIVE);
+    assert_ne!(swapped.header().unwrap().byte_order, ByteOrder::NATIVE);
+    assert_eq!(native.header().unwrap().table_length, swapped.header().unwrap().table_length);
+
+    assert_eq!(contents(&native), contents(&swapped));
+    assert_eq!(swapped.find("/java.base/java/lang/Compact.class").unwrap().read_to_vec().unwrap(), common::class_bytes("java/lang/Compact"));
+    assert_eq!(swapped.package_to_module(std::ffi::CStr::from_bytes_with_nul(b"java/util/logging\0").unwrap()).unwrap().to_str().unwrap(), "java.logging");
+    assert!(swapped.find_class("java.util.Map$Entry").is_ok());
+    assert!(swapped.verify().unwrap().is_ok());
+}
+
+#[test] fn swapped_resource_headers() {
+    let mut f = common::Fixture::default();
+    f.swap_byte_order();
+    let zipped = f.zip(b"some text");
+    let foreign = match ByteOrder::NATIVE { ByteOrder::LittleEndian => ByteOrder::BigEndian, ByteOrder::BigEndian => ByteOrder::LittleEndian };
+    assert!(jimage::compression::ResourceHeader::read(&zipped).is_none());
+    assert_eq!(jimage::compression::ResourceHeader::read_with_byte_order(&zipped, foreign).unwrap().uncompressed_size, 9);
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_libjimage_swapped() {
+    let jdk = common::local_jdk();
+    let err = common::load_local_libjimage(&jdk).open(fixture(true).write("byte_order_swapped_libjimage.jimage")).err().unwrap();
+    assert!(err.to_string().contains("open_native"), "{}", err);
+}
@@ -0,0 +1,27 @@
+mod common;
+
+use jimage::Error;
+use std::io::ErrorKind;
+
+#[test] fn native_resource_path() {
+    let file = jimage::File::open_native(common::Fixture::small().write("capabilities_native.jimage")).unwrap();
+    for name in ["/java.base/java/lang/Object.class", "/java.base/module-info.class", "/java.logging/java/util/logging/Logger.class"].iter() {
+        let path = file.find(name).unwrap().path().unwrap();
+        assert_eq!(path.to_string(), *name);
+    }
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_libjimage_capabilities() {
+    let jdk = common::local_jdk();
+    let lib = common::load_local_libjimage(&jdk);
+    let caps = lib.capabilities();
+    let file = lib.open(common::Fixture::small().write("capabilities_libjimage.jimage")).unwrap();
+    let resource = file.find("/java.base/java/lang/Object.class").unwrap();
+    if caps.resource_path {
+        assert_eq!(resource.path().unwrap().to_string(), "/java.base/java/lang/Object.class");
+    } else {
+        let err = resource.path().err().unwrap();
+        assert!(matches!(err, Error::Unsupported { .. }), "{:?}", err);
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+    }
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use std::io::ErrorKind;
+
+fn check(file: &jimage::File) {
+    assert_eq!(file.find_class("java.lang.Object").unwrap().read_to_vec().unwrap(), common::class_bytes("java/lang/Object"));
+    assert_eq!(file.find_class("java/lang/String").unwrap().read_to_vec().unwrap(), common::class_bytes("java/lang/String"));
+    assert_eq!(file.find_class("java.util.Map$Entry").unwrap().read_to_vec().unwrap(), common::class_bytes("java/util/Map$Entry"));
+    assert_eq!(file.find_class("java/util/logging/Logger").unwrap().read_to_vec().unwrap(), common::class_bytes("java/util/logging/Logger"));
+
+    let unknown_package = file.find_class("com.example.Missing").err().unwrap();
+    assert_eq!(unknown_package.kind(), ErrorKind::NotFound);
+    assert!(unknown_package.to_string().contains("unknown package"), "{}", unknown_package);
+    assert!(matches!(&unknown_package, jimage::Error::UnknownPackage { class, package, .. } if class == "com.example.Missing" && package == "com/example"), "{:?}", unknown_package);
+
+    let unknown_class = file.find_class("java.lang.Missing").err().unwrap();
+    assert_eq!(unknown_class.kind(), ErrorKind::NotFound);
+    assert!(unknown_class.to_string().contains("no such class"), "{}", unknown_class);
+    assert!(matches!(&unknown_class, jimage::Error::NotFound { resource, .. } if resource == "java.lang.Missing"), "{:?}", unknown_class);
+
+    assert_eq!(file.find_class("module-info").err().unwrap().kind(), ErrorKind::NotFound);
+    for bad in ["", ".", "java.lang.", "/java/lang/Object", "java.lang.Obj\0ect"].iter() {
+        assert_eq!(file.find_class(bad).err().unwrap().kind(), ErrorKind::InvalidInput, "{:?}", bad);
+    }
+}
+
+#[test] fn fixture_find_class() {
+    check(&jimage::File::open_native(common::Fixture::small().write("find_class.jimage")).unwrap());
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_libjimage_find_class() {
+    let jdk = common::local_jdk();
+    check(&common::load_local_libjimage(&jdk).open(common::Fixture::small().write("find_class_libjimage.jimage")).unwrap());
+}
@@ -0,0 +1,151 @@
+// Shared test helpers: fixtures built with jimage::write::ImageBuilder, and JDK discovery.
+
+#![allow(dead_code)]
+
+use jimage::write::ImageBuilder;
+use jimage::ByteOrder;
+use std::path::{Path, PathBuf};
+
+/// A small set of resources to be written as a jimage file.
+#[derive(Default)]
+pub struct Fixture {
+    builder:    ImageBuilder,
+}
+
+impl Fixture {
+    /// The fixture most tests use: a handful of resources across two modules.
+    pub fn small() -> Self {
+        let mut f = Self::default();
+        f.add("/java.base/java/lang/Object.class",          class_bytes("java/lang/Object"));
+        f.add("/java.base/java/lang/String.class",          class_bytes("java/lang/String"));
+        f.add("/java.base/java/util/Map$Entry.class",       class_bytes("java/util/Map$Entry"));
+        f.add("/java.base/java/util/Map.class",             class_bytes("java/util/Map"));
+        f.add("/java.base/module-info.class",               class_bytes("module-info"));
+        f.add("/java.base/jdk/internal/icu/impl/data/icudt64b/nfc.nrm", b"not really icu data".to_vec());
+        f.add("/java.logging/java/util/logging/Logger.class", class_bytes("java/util/logging/Logger"));
+        f.add("/java.logging/module-info.class",            class_bytes("module-info"));
+        f
+    }
+
+    /// Write the image (and resource headers created from now on) in the opposite of host byte order.
+    pub fn swap_byte_order(&mut self) {
+        let swapped = match self.builder.byte_order() { ByteOrder::LittleEndian => ByteOrder::BigEndian, ByteOrder::BigEndian => ByteOrder::LittleEndian };
+        self.builder.set_byte_order(swapped);
+    }
+
+    pub fn add(&mut self, path: &str, bytes: Vec<u8>) { let (module, path) = split(path); self.builder.add(module, path, bytes).unwrap(); }
+
+    /// Add a resource whose content is already compressed (see [Fixture::compress]) from `uncompressed_size` bytes.
+    pub fn add_compressed(&mut self, path: &str, stored: Vec<u8>, uncompressed_size: u64) { let (module, path) = split(path); self.builder.add_compressed(module, path, stored, uncompressed_size).unwrap(); }
+
+    /// Intern a string into the strings table, returning its offset.
+    pub fn string(&mut self, s: &str) -> u32 { self.builder.string(s) }
+
+    /// Prefix a decompressor `payload` with a resource header.
+    pub fn compress(&mut self, decompressor: &str, payload: &[u8], uncompressed_size: u64) -> Vec<u8> { self.builder.compress(decompressor, payload, uncompressed_size) }
+
+    /// Compress `data` with the "zip" decompressor.
+    pub fn zip(&mut self, data: &[u8]) -> Vec<u8> { self.builder.zip(data) }
+
+    /// Write the fixture to a fresh file under the target directory, returning its path.
+    pub fn write(&self, name: &str) -> PathBuf {
+        let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+        self.builder.write(&path).unwrap();
+        path
+    }
+
+    /// Serialize the fixture in host byte order, unless swapped.
+    pub fn to_bytes(&self) -> Vec<u8> { self.builder.to_bytes().unwrap() }
+}
+
+/// "/java.base/java/lang/Object.class" → ("java.base", "java/lang/Object.class")
+fn split(path: &str) -> (&str, &str) {
+    let path = &path[1..];
+    let slash = path.find('/').unwrap();
+    (&path[..slash], &path[slash+1..])
+}
+
+/// A tiny (not actually loadable) class file: magic, version, and an empty constant pool referencing `name`.
+pub fn class_bytes(name: &str) -> Vec<u8> {
+    let mut v = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 55];
+    v.extend_from_slice(&2u16.to_be_bytes());
+    v.push(1);
+    v.extend_from_slice(&(name.len() as u16).to_be_bytes());
+    v.extend_from_slice(name.as_bytes());
+    v
+}
+
+/// A minimal `module-info.class` declaring module `name`, with no requires, exports, etc.
+pub fn module_info(name: &str) -> Vec<u8> {
+    let utf8 = |s: &str| { let mut v = vec![1]; v.extend_from_slice(&(s.len() as u16).to_be_bytes()); v.extend_from_slice(s.as_bytes()); v };
+    let mut v = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 53];
+    v.extend_from_slice(&6u16.to_be_bytes());          // constant pool count
+    v.extend(utf8("module-info"));                      // #1
+    v.extend_from_slice(&[7, 0, 1]);                    // #2 Class #1
+    v.extend(utf8(name));                               // #3
+    v.extend_from_slice(&[19, 0, 3]);                   // #4 Module #3
+    v.extend(utf8("Module"));                           // #5
+    v.extend_from_slice(&[0x80, 0, 0, 2, 0, 0]);        // ACC_MODULE, this_class #2, no super_class
+    v.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1]);     // no interfaces, fields, or methods, 1 attribute
+    v.extend_from_slice(&[0, 5, 0, 0, 0, 16, 0, 4]);    // "Module", 16 bytes, module_name_index #4
+    v.extend_from_slice(&[0; 14]);                      // flags, version, and no requires/exports/opens/uses/provides
+    v
+}
+
+/// A zip of stored (uncompressed) entries
+pub fn zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let crc32 = |data: &[u8]| !data.iter().fold(!0u32, |crc, &b| (0..8).fold(crc ^ u32::from(b), |crc, _| if crc & 1 != 0 { 0xEDB88320 ^ (crc >> 1) } else { crc >> 1 }));
+    let (mut zip, mut central) = (Vec::new(), Vec::new());
+    for (name, data) in entries.iter() {
+        let mut header = Vec::new();
+        header.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0]); // version needed, flags, method, time
+        header.extend_from_slice(&[0, 0]); // date
+        header.extend_from_slice(&crc32(data).to_le_bytes());
+        header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        header.extend_from_slice(&[0, 0]); // extra length
+
+        central.extend_from_slice(&0x02014b50u32.to_le_bytes());
+        central.extend_from_slice(&[20, 0]); // version made by
+        central.extend_from_slice(&header);
+        central.extend_from_slice(&[0; 10]); // comment length, disk, internal & external attributes
+        central.extend_from_slice(&(zip.len() as u32).to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+
+        zip.extend_from_slice(&0x04034b50u32.to_le_bytes());
+        zip.extend_from_slice(&header);
+        zip.extend_from_slice(name.as_bytes());
+        zip.extend_from_slice(data);
+    }
+    let start = zip.len() as u32;
+    zip.extend_from_slice(&central);
+    zip.extend_from_slice(&0x06054b50u32.to_le_bytes());
+    zip.extend_from_slice(&[0; 4]);
+    zip.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    zip.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    zip.extend_from_slice(&(central.len() as u32).to_le_bytes());
+    zip.extend_from_slice(&start.to_le_bytes());
+    zip.extend_from_slice(&[0, 0]);
+    zip
+}
+
+/// Find a local JDK 9+ install to compare against - of the same architecture, so its library loads.
+///
+/// Not every machine (CI included) has one, so tests using this are `#[ignore]`d: run them with `cargo test -- --ignored`,
+/// and they'll fail rather than pass vacuously if there's no JDK to be found.
+pub fn local_jdk() -> PathBuf {
+    jimage::discover().into_iter().find(|jdk| jdk.load_library().is_ok()).map(|jdk| jdk.home().to_path_buf())
+        .expect("no local JDK 9+ of this architecture found - install one, or set JAVA_HOME")
+}
+
+/// Find a local JDK install with `jmods/`, as [local_jdk] does.
+pub fn local_jdk_with_jmods() -> jimage::Jdk {
+    jimage::Jdk::newest(|jdk| jdk.home().join("jmods").join("java.logging.jmod").exists())
+        .expect("no local JDK with jmods/ found - install one, or set JAVA_HOME")
+}
+
+/// Load a local JDK's jimage library.
+pub fn load_local_libjimage(jdk: &Path) -> jimage::Library {
+    jimage::Jdk::from_home(jdk).unwrap().load_library().unwrap()
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use std::ffi::*;
+
+fn cstr(s: &[u8]) -> &CStr { CStr::from_bytes_with_nul(s).unwrap() }
+
+/// Encode an index the way jlink's `CompressIndexes` does.
+fn index(v: u32) -> Vec<u8> {
+    if      v < 0x20        { vec![0xA0 | v as u8] }
+    else if v < 0x2000      { vec![0xC0 | (v >> 8) as u8, v as u8] }
+    else if v < 0x20_0000   { vec![0xE0 | (v >> 16) as u8, (v >> 8) as u8, v as u8] }
+    else                    { v.to_be_bytes().to_vec() }
+}
+
+/// A class file with a few constant pool entries, plus its "compact-cp" encoding.
+fn compact_cp_class(f: &mut common::Fixture) -> (Vec<u8>, Vec<u8>) {
+    let header = [0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 55, 0, 6];
+    let long = [1, 2, 3, 4, 5, 6, 7, 8];
+    let trailer = [0x00, 0x21, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00];
+
+    let mut class = header.to_vec();
+    class.extend_from_slice(&[1, 0, 16]); class.extend_from_slice(b"java/lang/Object");
+    class.extend_from_slice(&[1, 0, 22]); class.extend_from_slice(b"(Ljava/lang/String;I)V");
+    class.push(5); class.extend_from_slice(&long);
+    class.extend_from_slice(&[1, 0, 4]); class.extend_from_slice(b"kept");
+    class.extend_from_slice(&trailer);
+
+    let mut compact = header.to_vec();
+    compact.push(23); compact.extend(index(f.string("java/lang/Object")));
+    let indexes = [index(f.string("java/lang")), index(f.string("String"))].concat();
+    compact.push(25); compact.extend(index(f.string("(L;I)V"))); compact.extend(index(indexes.len() as u32)); compact.extend(indexes);
+    compact.push(5); compact.extend_from_slice(&long);
+    compact.extend_from_slice(&[1, 0, 4]); compact.extend_from_slice(b"kept");
+    compact.extend_from_slice(&trailer);
+
+    (class, compact)
+}
+
+fn compressed_fixture() -> (common::Fixture, Vec<u8>, Vec<u8>) {
+    let mut f = common::Fixture::small();
+    let text = b"Lorem ipsum dolor sit amet, lorem ipsum dolor sit amet, lorem ipsum dolor sit amet".to_vec();
+    let (class, compact) = compact_cp_class(&mut f);
+
+    let zipped = f.zip(&text);
+    f.add_compressed("/java.base/zipped.txt", zipped, text.len() as u64);
+
+    let compact = f.compress("compact-cp", &compact, class.len() as u64);
+    f.add_compressed("/java.base/java/lang/Compact.class", compact.clone(), class.len() as u64);
+
+    let both = f.zip(&compact);
+    f.add_compressed("/java.base/java/lang/Both.class", both, class.len() as u64);
+
+    (f, text, class)
+}
+
+fn read(file: &jimage::File, module: &[u8], name: &[u8]) -> Vec<u8> {
+    let res = file.find_resource(cstr(module), cstr(b"9.0\0"), cstr(name)).unwrap();
+    let mut v = vec![0; res.size() as usize];
+    assert_eq!(res.get(&mut v).unwrap(), res.size());
+    v
+}
+
+#[test] fn native_decompression() {
+    let (f, text, class) = compressed_fixture();
+    let file = jimage::File::open_native(f.write("compression_native.jimage")).unwrap();
+    assert_eq!(read(&file, b"java.base\0", b"zipped.txt\0"), text);
+    assert_eq!(read(&file, b"java.base\0", b"java/lang/Compact.class\0"), class);
+    assert_eq!(read(&file, b"java.base\0", b"java/lang/Both.class\0"), class);
+    assert_eq!(read(&file, b"java.base\0", b"java/lang/Object.class\0"), common::class_bytes("java/lang/Object"));
+}
+
+#[test] fn unknown_decompressor() {
+    let mut f = common::Fixture::small();
+    let bogus = f.compress("bogus", b"data", 4);
+    f.add_compressed("/java.base/bogus.txt", bogus, 4);
+    let file = jimage::File::open_native(f.write("compression_unknown.jimage")).unwrap();
+    let res = file.find_resource(cstr(b"java.base\0"), cstr(b"9.0\0"), cstr(b"bogus.txt\0")).unwrap();
+    let err = res.get(&mut [0; 4]).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("bogus"), "{}", err);
+}
+
+#[test] fn decompress_truncated() {
+    let mut f = common::Fixture::default();
+    let zipped = f.zip(b"some text");
+    assert_eq!(jimage::compression::ResourceHeader::read(&zipped).unwrap().uncompressed_size, 9);
+    assert!(jimage::compression::decompress(&zipped[..zipped.len()-1], &[], 9).is_err());
+    assert!(jimage::compression::ResourceHeader::read(b"not a header, but long enough to be one").is_none());
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_libjimage_agrees() {
+    let jdk = common::local_jdk();
+    let (f, text, class) = compressed_fixture();
+    let path = f.write("compression_libjimage.jimage");
+    let file = common::load_local_libjimage(&jdk).open(&path).unwrap();
+    assert_eq!(read(&file, b"java.base\0", b"java/lang/Compact.class\0"), class);
+    assert_eq!(read(&file, b"java.base\0", b"java/lang/Both.class\0"), class);
+    assert_eq!(read(&file, b"java.base\0", b"zipped.txt\0"), text);
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use jimage::Header;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// Tracks the largest allocation made by each thread, so tests can check hostile sizes never get allocated
+struct PeakAlloc;
+
+thread_local! { static PEAK_ALLOC : Cell<usize> = const { Cell::new(0) }; }
+
+fn note_alloc(size: usize) { let _ = PEAK_ALLOC.try_with(|peak| peak.set(peak.get().max(size))); }
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 { note_alloc(layout.size()); System.alloc(layout) }
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 { note_alloc(layout.size()); System.alloc_zeroed(layout) }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 { note_alloc(new_size); System.realloc(ptr, layout, new_size) }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) { System.dealloc(ptr, layout) }
+}
+
+#[global_allocator] static ALLOCATOR : PeakAlloc = PeakAlloc;
+
+/// Exercise everything that reads the index or resource contents.  Errors are fine, panics aren't.
+fn exercise(bytes: Vec<u8>) {
+    let file = match jimage::File::from_bytes(bytes) { Ok(file) => file, Err(_) => return };
+    let _ = file.header();
+    let _ = file.verify();
+    for entry in file.resources() {
+        let _ = entry.path();
+        if let Ok(res) = entry.resource(&file) { let _ = res.bytes(); let _ = res.get(&mut [0; 16]); }
+    }
+    file.visit(|r| { let _ = r.resource().and_then(|r| r.read_to_vec()); jimage::VisitResult::Continue });
+    let _ = file.find("/java.base/java/lang/Object.class").and_then(|r| r.read_to_vec());
+    let _ = file.find_class("java.util.Map$Entry");
+    let _ = file.packages_of("java.base");
+    let _ = file.modules().count();
+}
+
+/// A deterministic xorshift, so failures are reproducible
+struct Rng(u64);
+impl Rng {
+    fn next(&mut self) -> u64 { self.0 ^= self.0 << 13; self.0 ^= self.0 >> 7; self.0 ^= self.0 << 17; self.0 }
+    fn below(&mut self, n: usize) -> usize { (self.next() % n as u64) as usize }
+}
+
+fn fixture() -> Vec<u8> {
+    let mut f = common::Fixture::small();
+    let zipped = f.zip(b"compressed text, compressed text, compressed text");
+    f.add_compressed("/java.base/zipped.txt", zipped, 50);
+    let class = common::class_bytes("java/lang/Compact");
+    let compact = f.compress("compact-cp", &class, class.len() as u64);
+    f.add_compressed("/java.base/java/lang/Compact.class", compact, class.len() as u64);
+    f.to_bytes()
+}
+
+#[test] fn truncated_images() {
+    let bytes = fixture();
+    for len in 0 .. bytes.len() { exercise(bytes[..len].to_vec()); }
+}
+
+#[test] fn byte_flipped_index() {
+    let bytes = fixture();
+    let index_size = Header::read(&bytes).unwrap().index_size() as usize;
+    for i in 0 .. index_size {
+        for &flip in [0x01, 0x80, 0xFF].iter() {
+            let mut bytes = bytes.clone();
+            bytes[i] ^= flip;
+            exercise(bytes);
+        }
+    }
+}
+
+#[test] fn random_corruption() {
+    let bytes = fixture();
+    let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+    for _ in 0 .. 2000 {
+        let mut bytes = bytes.clone();
+        for _ in 0 .. 1 + rng.below(8) {
+            let i = rng.below(bytes.len());
+            bytes[i] = rng.next() as u8;
+        }
+        exercise(bytes);
+    }
+}
+
+#[test] fn hostile_headers() {
+    let bytes = fixture();
+    for field in 2 .. 7 {
+        for &value in [0, 1, 0x7FFF_FFFF, 0x8000_0000, 0xFFFF_FFFF].iter() {
+            let mut bytes = bytes.clone();
+            bytes[field*4 .. field*4+4].copy_from_slice(&u32::to_ne_bytes(value));
+            exercise(bytes);
+        }
+    }
+}
+
+#[test] fn hostile_compression() {
+    let mut f = common::Fixture::small();
+
+    // a header claiming an absurd uncompressed size
+    let huge = f.compress("zip", b"not really zlib", u64::MAX);
+    f.add_compressed("/java.base/huge.txt", huge, u64::MAX);
+
+    // compact-cp strings referencing the largest string in the table, over and over
+    let big = f.string(&"x".repeat(60_000));
+    let mut payload = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 55, 0xFF, 0xFF];
+    for _ in 0 .. 0xFFFE { payload.push(23); payload.extend_from_slice(&big.to_be_bytes()); }
+    let bomb = f.compress("compact-cp", &payload, 100);
+    f.add_compressed("/java.base/bomb.class", bomb, 100);
+
+    // a deep chain of headers
+    let mut nested = f.zip(b"innermost");
+    for _ in 0 .. 32 { nested = f.zip(&nested); }
+    f.add_compressed("/java.base/nested.txt", nested, 9);
+
+    let file = jimage::File::from_bytes(f.to_bytes()).unwrap();
+    for name in ["/java.base/huge.txt", "/java.base/bomb.class", "/java.base/nested.txt"].iter() {
+        assert!(file.find(name).unwrap().bytes().is_err(), "{}", name);
+    }
+    assert_eq!(file.verify().unwrap().problems.len(), 3);
+}
+
+#[test] fn zip_larger_than_declared() {
+    // a well formed zip layer, within its own header's size, but far larger than the location's declared size
+    const BOMB : usize = 16 << 20;
+    let mut f = common::Fixture::small();
+    let zipped = f.zip(&vec![0; BOMB]);
+    f.add_compressed("/java.base/bomb.txt", zipped, 10);
+    let file = jimage::File::from_bytes(f.to_bytes()).unwrap();
+    let res = file.find("/java.base/bomb.txt").unwrap();
+    assert_eq!(res.size(), 10);
+
+    PEAK_ALLOC.with(|peak| peak.set(0));
+    let corrupted = |err: Option<jimage::Error>| match err {
+        Some(jimage::Error::Corrupted { resource: Some(resource), .. }) => assert_eq!(resource, "/java.base/bomb.txt"),
+        other => panic!("expected Error::Corrupted, got {:?}", other),
+    };
+    corrupted(res.bytes().err());
+    corrupted(res.get(&mut [0; 10]).err());
+    corrupted(res.read_to_vec().err());
+    let peak = PEAK_ALLOC.with(Cell::get);
+    assert!(peak < BOMB / 16, "allocated {} bytes", peak);
+    assert_eq!(file.verify().unwrap().problems.len(), 1);
+}
@@ -0,0 +1,44 @@
+use std::path::{Path, PathBuf};
+
+fn fake_jdk(name: &str) -> PathBuf {
+    let home = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("discover").join(name);
+    let jdk = jimage::Jdk::from_home(&home);
+    if jdk.is_none() {
+        for path in [home.join("lib").join("modules"), home.join("bin").join(jimage::Library::NAME), home.join("lib").join(jimage::Library::NAME), home.join("bin").join("java")].iter() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"").unwrap();
+        }
+    }
+    home
+}
+
+#[test] fn from_home() {
+    let home = fake_jdk("from-home");
+    let jdk = jimage::Jdk::from_home(&home).unwrap();
+    assert_eq!(jdk.home(), home);
+    assert_eq!(jdk.modules(), home.join("lib").join("modules"));
+    assert!(jdk.libjimage().ends_with(jimage::Library::NAME));
+    assert!(jimage::Jdk::from_home(home.join("lib")).is_none());
+    assert!(jimage::Jdk::from_home(Path::new(env!("CARGO_TARGET_TMPDIR")).join("discover-missing")).is_none());
+}
+
+// The only test in this binary touching JAVA_HOME / PATH, so it can't race with the others.
+#[test] fn env() {
+    let java_home = fake_jdk("java-home");
+    let on_path = fake_jdk("on-path");
+    let mut path = vec![on_path.join("bin")];
+    path.extend(std::env::split_paths(&std::env::var_os("PATH").unwrap_or_default()));
+    std::env::set_var("JAVA_HOME", &java_home);
+    std::env::set_var("PATH", std::env::join_paths(path).unwrap());
+
+    let jdks = jimage::discover();
+    assert_eq!(jdks[0].home(), java_home);
+    if !cfg!(windows) { assert_eq!(jdks[1].home(), on_path.canonicalize().unwrap()); }
+    assert_eq!(jdks.iter().filter(|jdk| jdk.home() == java_home).count(), 1);
+
+    // The fake JDKs' empty libraries fail to load, so find() should fall through to a real JDK, if there is one
+    match jimage::Library::find() {
+        Ok(lib) => { lib.open(jdks[2].modules()).unwrap(); },
+        Err(err) => { assert_eq!(jdks.len(), 2); assert!(matches!(err, jimage::Error::LibraryLoad { .. }), "{:?}", err); },
+    }
+}
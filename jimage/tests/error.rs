@@ -0,0 +1,95 @@
+mod common;
+
+use jimage::Error;
+use std::ffi::*;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+fn cstr(s: &[u8]) -> &CStr { CStr::from_bytes_with_nul(s).unwrap() }
+
+fn write(name: &str, bytes: &[u8]) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, bytes).unwrap();
+    path
+}
+
+fn bad_version() -> Vec<u8> {
+    let mut bytes = common::Fixture::small().to_bytes();
+    bytes[4..8].copy_from_slice(&(2u32 << 16 | 3).to_ne_bytes());
+    bytes
+}
+
+#[test] fn open_errors() {
+    let path = write("error_bad_version.jimage", &bad_version());
+    match jimage::File::open_native(&path).err().unwrap() {
+        Error::BadVersion { path: Some(p), major: 2, minor: 3 } => assert_eq!(p, path),
+        other => panic!("expected BadVersion, got {:?}", other),
+    }
+
+    let path = write("error_bad_magic.jimage", b"definitely not a jimage file, but long enough to have a header");
+    let err = jimage::File::open_mmap(&path).err().unwrap();
+    assert!(matches!(err, Error::BadMagic { .. }), "{:?}", err);
+    assert_eq!(err.path(), Some(path.as_path()));
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("error_missing.jimage");
+    let err = jimage::File::open_native(&path).err().unwrap();
+    assert!(matches!(&err, Error::Io { source, .. } if source.kind() == ErrorKind::NotFound), "{:?}", err);
+    assert!(err.to_string().contains("error_missing.jimage"), "{}", err);
+
+    let err = jimage::Library::load(PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("no-such-libjimage.so")).err().unwrap();
+    assert!(matches!(err, Error::LibraryLoad { .. }), "{:?}", err);
+}
+
+#[test] fn lookup_errors() {
+    let path = common::Fixture::small().write("error_lookup.jimage");
+    let file = jimage::File::open_native(&path).unwrap();
+    match file.find_resource(cstr(b"java.base\0"), cstr(b"9.0\0"), cstr(b"java/lang/Missing.class\0")).err().unwrap() {
+        Error::NotFound { path: Some(p), resource, .. } => { assert_eq!(p, path); assert_eq!(resource, "/java.base/java/lang/Missing.class"); },
+        other => panic!("expected NotFound, got {:?}", other),
+    }
+    assert!(matches!(file.find("no-module").err().unwrap(), Error::InvalidInput { .. }));
+    assert!(matches!(file.packages_of("java.nope").err().unwrap(), Error::NotFound { .. }));
+}
+
+#[test] fn corrupted_resource() {
+    let mut f = common::Fixture::small();
+    let bogus = f.compress("bogus", b"data", 4);
+    f.add_compressed("/java.base/bogus.txt", bogus, 4);
+    let file = jimage::File::open_native(f.write("error_corrupted.jimage")).unwrap();
+    match file.find("/java.base/bogus.txt").unwrap().bytes().err().unwrap() {
+        Error::Corrupted { path: Some(_), resource: Some(resource), message } => {
+            assert_eq!(resource, "/java.base/bogus.txt");
+            assert!(message.contains("bogus"), "{}", message);
+        },
+        other => panic!("expected Corrupted, got {:?}", other),
+    }
+}
+
+#[test] fn into_io_error() {
+    fn open(path: &std::path::Path) -> std::io::Result<jimage::File> { Ok(jimage::File::open_native(path)?) }
+    let path = write("error_into_io.jimage", &bad_version());
+    let err = open(&path).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.to_string().contains("2.3"), "{}", err);
+    assert!(matches!(err.into_inner().unwrap().downcast::<Error>().map(|e| *e), Ok(Error::BadVersion { .. })));
+
+    let missing = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("error_into_io_missing.jimage");
+    assert_eq!(open(&missing).err().unwrap().kind(), ErrorKind::NotFound);
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_libjimage_open_errors() {
+    let jdk = common::local_jdk();
+    let lib = common::load_local_libjimage(&jdk);
+    let err = lib.open(write("error_libjimage_bad_version.jimage", &bad_version())).err().unwrap();
+    assert!(matches!(err, Error::BadVersion { major: 2, minor: 3, .. }), "{:?}", err);
+    let err = lib.open(write("error_libjimage_bad_magic.jimage", b"definitely not a jimage file, but long enough to have a header")).err().unwrap();
+    assert!(matches!(err, Error::BadMagic { path: Some(_) }), "{:?}", err);
+
+    let path = common::Fixture::small().write("error_libjimage_lookup.jimage");
+    let file = lib.open(&path).unwrap();
+    match file.find("/java.base/java/lang/Missing.class").err().unwrap() {
+        Error::NotFound { path: Some(p), resource, .. } => { assert_eq!(p, path); assert_eq!(resource, "/java.base/java/lang/Missing.class"); },
+        other => panic!("expected NotFound, got {:?}", other),
+    }
+}
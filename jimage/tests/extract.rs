@@ -0,0 +1,119 @@
+mod common;
+
+use jimage::{ExtractFilter, ResourcePath};
+use std::path::{Path, PathBuf};
+
+fn fresh_dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+/// Every file under `dir`, as sorted `/`-separated paths relative to it
+fn files(dir: &Path) -> Vec<String> {
+    fn walk(root: &Path, dir: &Path, out: &mut Vec<String>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() { walk(root, &path, out); }
+            else { out.push(path.strip_prefix(root).unwrap().components().map(|c| c.as_os_str().to_str().unwrap()).collect::<Vec<_>>().join("/")); }
+        }
+    }
+    let mut out = Vec::new();
+    walk(dir, dir, &mut out);
+    out.sort();
+    out
+}
+
+#[test] fn filter_globs() {
+    let path = |s: &str| ResourcePath::parse(s).unwrap();
+    let mut filter = ExtractFilter::new();
+    assert!(filter.matches(&path("/java.base/java/lang/Object.class")));
+
+    filter.include("java.base/java/util/**").unwrap().include("/*/module-info.class").unwrap().exclude("java.base/java/util/*$*.class").unwrap();
+    assert!( filter.matches(&path("/java.base/java/util/Map.class")));
+    assert!( filter.matches(&path("/java.base/java/util/concurrent/atomic/AtomicInteger.class")));
+    assert!(!filter.matches(&path("/java.base/java/util/Map$Entry.class")));
+    assert!( filter.matches(&path("/java.base/java/util/concurrent/Foo$Bar.class")), "* doesn't cross segments");
+    assert!( filter.matches(&path("/java.logging/module-info.class")));
+    assert!(!filter.matches(&path("/java.logging/java/util/logging/Logger.class")));
+    assert!(!filter.matches(&path("/java.base/java/lang/Object.class")));
+
+    let mut filter = ExtractFilter::new();
+    filter.include("java.?ase/**/Object.*").unwrap();
+    assert!( filter.matches(&path("/java.base/java/lang/Object.class")));
+    assert!( filter.matches(&path("/java.base/Object.txt")));
+    assert!(!filter.matches(&path("/java.logging/java/lang/Object.class")));
+
+    for bad in ["", "/", "java.base//x", "java.base/java**/x", "java.base/a\0b"].iter() {
+        let err = ExtractFilter::new().include(bad).err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput, "{:?}", bad);
+    }
+}
+
+#[test] fn extract_all() {
+    let mut fixture = common::Fixture::small();
+    let zipped = fixture.zip(b"zipped zipped zipped zipped");
+    fixture.add_compressed("/java.base/META-INF/zipped.txt", zipped, 27);
+    let file = jimage::File::open_native(fixture.write("extract_all.jimage")).unwrap();
+    let dir = fresh_dir("extract_all");
+
+    let mut progress = Vec::new();
+    let n = file.extract_to_with_progress(&dir, &ExtractFilter::new(), |p| {
+        assert_eq!(p.path, dir.join(p.resource.module()).join(p.resource.path()));
+        assert_eq!(std::fs::metadata(p.path).unwrap().len(), p.bytes);
+        progress.push((p.resource.to_string(), p.done, p.total));
+    }).unwrap();
+    assert_eq!(n, 9);
+    assert_eq!(progress, [
+        "/java.base/META-INF/zipped.txt",
+        "/java.base/java/lang/Object.class",
+        "/java.base/java/lang/String.class",
+        "/java.base/java/util/Map$Entry.class",
+        "/java.base/java/util/Map.class",
+        "/java.base/jdk/internal/icu/impl/data/icudt64b/nfc.nrm",
+        "/java.base/module-info.class",
+        "/java.logging/java/util/logging/Logger.class",
+        "/java.logging/module-info.class",
+    ].iter().enumerate().map(|(i, r)| (r.to_string(), i + 1, 9)).collect::<Vec<_>>(), "sorted, deterministic order");
+
+    assert_eq!(files(&dir).len(), 9);
+    assert_eq!(std::fs::read(dir.join("java.base/java/lang/Object.class")).unwrap(), common::class_bytes("java/lang/Object"));
+    assert_eq!(std::fs::read(dir.join("java.base/META-INF/zipped.txt")).unwrap(), b"zipped zipped zipped zipped");
+    assert_eq!(std::fs::read(dir.join("java.logging/module-info.class")).unwrap(), common::class_bytes("module-info"));
+}
+
+#[test] fn extract_filtered() {
+    let file = jimage::File::open_native(common::Fixture::small().write("extract_filtered.jimage")).unwrap();
+    let dir = fresh_dir("extract_filtered");
+    let mut filter = ExtractFilter::new();
+    filter.include("java.base/java/util/**").unwrap().exclude("**/*$*").unwrap();
+    assert_eq!(file.extract_to(&dir, &filter).unwrap(), 1);
+    assert_eq!(files(&dir), ["java.base/java/util/Map.class"]);
+
+    let mut nothing = ExtractFilter::new();
+    nothing.include("java.desktop/**").unwrap();
+    assert_eq!(file.extract_to(fresh_dir("extract_nothing"), &nothing).unwrap(), 0);
+}
+
+#[test] fn extract_refuses_to_escape() {
+    let mut fixture = common::Fixture::small();
+    fixture.add("/java.base/../../escaped.txt", b"oops".to_vec());
+    let file = jimage::File::open_native(fixture.write("extract_escape.jimage")).unwrap();
+    let dir = fresh_dir("extract_escape/inner");
+
+    let err = file.extract_to(&dir, &ExtractFilter::new()).err().unwrap();
+    assert!(matches!(&err, jimage::Error::Corrupted { resource: Some(r), .. } if r == "/java.base/../../escaped.txt"), "{:?}", err);
+    assert!(!dir.join("../escaped.txt").exists() && !dir.join("../../escaped.txt").exists());
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_jdk() {
+    let jdk = jimage::Jdk::from_home(common::local_jdk()).unwrap();
+    let file = jdk.open().unwrap();
+    let dir = fresh_dir("extract_local_jdk");
+    let mut filter = ExtractFilter::new();
+    filter.include("java.base/java/util/*").unwrap();
+    let n = file.extract_to(&dir, &filter).unwrap();
+    assert!(n > 100, "only {} resources in java.base/java/util?", n);
+    assert_eq!(files(&dir).len(), n);
+    assert_eq!(std::fs::read(dir.join("java.base/java/util/List.class")).unwrap(), file.find("/java.base/java/util/List.class").unwrap().read_to_vec().unwrap());
+}
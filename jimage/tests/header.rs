@@ -0,0 +1,52 @@
+mod common;
+
+use jimage::{ByteOrder, Header};
+
+#[test] fn fixture_header() {
+    let f = common::Fixture::small();
+    let bytes = f.to_bytes();
+    let file = jimage::File::open_native(f.write("header.jimage")).unwrap();
+    let header = file.header().unwrap();
+    assert_eq!(header, Header::read(&bytes).unwrap());
+    assert_eq!(header.magic, 0xCAFE_DADA);
+    assert_eq!((header.major_version, header.minor_version), (1, 0));
+    assert_eq!(header.byte_order, ByteOrder::NATIVE);
+    assert!(header.resource_count as usize >= file.resources().count());
+    assert!(header.table_length >= header.resource_count);
+
+    assert_eq!(header.redirect().start, Header::SIZE as u64);
+    assert_eq!(header.redirect().end - header.redirect().start, u64::from(header.table_length) * 4);
+    assert_eq!(header.offsets().start, header.redirect().end);
+    assert_eq!(header.locations().start, header.offsets().end);
+    assert_eq!(header.strings().start, header.locations().end);
+    assert_eq!(header.strings().end, header.index_size());
+    assert!(header.index_size() <= bytes.len() as u64);
+    assert_eq!(bytes[header.strings().start as usize], 0, "strings table should start with the empty string");
+}
+
+#[test] fn header_byte_order_and_version() {
+    let mut bytes = common::Fixture::small().to_bytes();
+    for word in bytes[..Header::SIZE].chunks_exact_mut(4) { word.reverse(); }
+    let swapped = Header::read(&bytes).unwrap();
+    assert_ne!(swapped.byte_order, ByteOrder::NATIVE);
+    assert_eq!((swapped.major_version, swapped.minor_version), (1, 0));
+
+    bytes[..4].copy_from_slice(&0xCAFE_DADAu32.to_ne_bytes());
+    bytes[4..8].copy_from_slice(&(7u32 << 16 | 2).to_ne_bytes());
+    let future = Header::read(&bytes).unwrap();
+    assert_eq!((future.major_version, future.minor_version), (7, 2));
+
+    assert!(matches!(Header::read(&bytes[..Header::SIZE-1]), Err(jimage::Error::BadMagic { .. })));
+    assert!(matches!(Header::read(&[0xAB; Header::SIZE]), Err(jimage::Error::BadMagic { .. })));
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_jdk_header() {
+    let jdk = common::local_jdk();
+    let modules = jdk.join("lib").join("modules");
+    let c_file = common::load_local_libjimage(&jdk).open(&modules).unwrap();
+    let r_file = jimage::File::open_mmap(&modules).unwrap();
+    let header = r_file.header().unwrap();
+    assert_eq!(c_file.header().unwrap(), header);
+    assert_eq!(header.byte_order, ByteOrder::NATIVE);
+    assert!(header.index_size() < std::fs::metadata(&modules).unwrap().len());
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use jimage::Version;
+use std::path::PathBuf;
+
+fn fake_jdk(name: &str, release: &str) -> jimage::Jdk {
+    let home = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("jdk").join(name);
+    std::fs::create_dir_all(home.join("lib")).unwrap();
+    std::fs::write(home.join("lib").join("modules"), common::Fixture::small().to_bytes()).unwrap();
+    let libjimage = if cfg!(windows) { home.join("bin").join(jimage::Library::NAME) } else { home.join("lib").join(jimage::Library::NAME) };
+    std::fs::create_dir_all(libjimage.parent().unwrap()).unwrap();
+    std::fs::write(libjimage, b"").unwrap();
+    std::fs::write(home.join("release"), release).unwrap();
+    jimage::Jdk::from_home(home).unwrap()
+}
+
+#[test] fn release() {
+    let jdk = fake_jdk("release", concat!(
+        "IMPLEMENTOR=\"Eclipse Adoptium\"\n",
+        "JAVA_RUNTIME_VERSION=\"17.0.2+8\"\n",
+        "JAVA_VERSION=\"17.0.2\"\n",
+        "MODULES=\"java.base java.logging\"\n",
+        "OS_ARCH=\"x86_64\"\n",
+        "SOURCE=\"\"\n",
+    ));
+    assert_eq!(jdk.version(), Some(Version { feature: 17, interim: 0, update: 2, patch: 0 }));
+    assert_eq!(jdk.implementor(), Some("Eclipse Adoptium"));
+    assert_eq!(jdk.os_arch(), Some("x86_64"));
+    assert_eq!(jdk.module_names().collect::<Vec<_>>(), ["java.base", "java.logging"]);
+    assert_eq!(jdk.release("JAVA_RUNTIME_VERSION"), Some("17.0.2+8"));
+    assert_eq!(jdk.release("SOURCE"), Some(""));
+    assert_eq!(jdk.release("MISSING"), None);
+
+    let file = jdk.open().unwrap();
+    assert_eq!(file.modules().collect::<Vec<_>>(), jdk.module_names().collect::<Vec<_>>());
+
+    let jdk = fake_jdk("vendor-release", "JAVA_VERSION=\"11.0.9.1.1\"\n");
+    assert_eq!(jdk.version(), Some(Version { feature: 11, interim: 0, update: 9, patch: 1 }));
+
+    let jdk = fake_jdk("no-release", "");
+    assert_eq!(jdk.version(), None);
+    assert_eq!(jdk.module_names().count(), 0);
+}
+
+#[test] fn version() {
+    assert_eq!("9".parse::<Version>().unwrap(), Version::new(9));
+    assert_eq!("11.0.12".parse::<Version>().unwrap(), Version { feature: 11, interim: 0, update: 12, patch: 0 });
+    assert_eq!("21-ea".parse::<Version>().unwrap(), Version::new(21));
+    assert_eq!("17.0.4.1+1".parse::<Version>().unwrap(), Version { feature: 17, interim: 0, update: 4, patch: 1 });
+    assert!("".parse::<Version>().is_err());
+    assert!("17.0.x".parse::<Version>().is_err());
+    assert_eq!("17.0.4.1.1".parse::<Version>().unwrap(), Version { feature: 17, interim: 0, update: 4, patch: 1 }, "vendor components are ignored");
+    assert!("17.0.4.1.x".parse::<Version>().is_err());
+
+    assert!(Version::new(9) < "11.0.12".parse().unwrap());
+    assert!("17.0.2".parse::<Version>().unwrap() < "17.0.10".parse().unwrap());
+    assert_eq!("17.0.2".parse::<Version>().unwrap().to_string(), "17.0.2");
+    assert_eq!("17.0.4.1".parse::<Version>().unwrap().to_string(), "17.0.4.1");
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_newest() {
+    common::local_jdk();
+    let jdk = jimage::Jdk::newest(|jdk| jdk.version() >= Some(Version::new(9))).unwrap();
+    assert!(jimage::discover().iter().all(|other| other.version() <= jdk.version()));
+    let file = jdk.open().unwrap();
+    assert!(file.find("/java.base/java/lang/Object.class").is_ok());
+    assert!(jdk.module_names().any(|m| m == "java.base"));
+}
@@ -0,0 +1,218 @@
+mod common;
+
+use jimage::jmod::{Jmod, Section};
+use std::convert::TryInto;
+use std::ffi::CStr;
+use std::process::Command;
+
+/// Every `(name, contents)` of a jmod's zip, in central directory order
+fn unzip(jmod: &[u8]) -> Vec<(String, Vec<u8>)> {
+    assert_eq!(&jmod[..4], b"JM\x01\x00");
+    let zip = &jmod[4..];
+    let u16_at = |o: usize| u16::from_le_bytes(zip[o..o+2].try_into().unwrap()) as usize;
+    let u32_at = |o: usize| u32::from_le_bytes(zip[o..o+4].try_into().unwrap()) as usize;
+
+    let eocd = zip.len() - 22;
+    assert_eq!(u32_at(eocd), 0x06054b50);
+    let (count, mut central) = (u16_at(eocd + 10), u32_at(eocd + 16));
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        assert_eq!(u32_at(central), 0x02014b50);
+        let (method, crc, compressed, size) = (u16_at(central + 10), u32_at(central + 16), u32_at(central + 20), u32_at(central + 24));
+        let name_len = u16_at(central + 28);
+        let name = std::str::from_utf8(&zip[central + 46 .. central + 46 + name_len]).unwrap().to_string();
+        let local = u32_at(central + 42);
+        assert_eq!(u32_at(local), 0x04034b50);
+        assert_eq!(u16_at(local + 26), name_len);
+        let data = &zip[local + 30 + name_len + u16_at(local + 28) ..][..compressed];
+        let data = match method {
+            0 => data.to_vec(),
+            8 => miniz_oxide::inflate::decompress_to_vec(data).unwrap(),
+            other => panic!("unexpected method {}", other),
+        };
+        assert_eq!(data.len(), size, "{}", name);
+        assert_ne!(crc, 0, "{}", name);
+        entries.push((name, data));
+        central += 46 + name_len;
+    }
+    entries
+}
+
+/// A jmod of stored (uncompressed) entries, as `jmod create` might write them
+fn jmod_of(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut jmod = b"JM\x01\x00".to_vec();
+    jmod.extend(common::zip(entries));
+    jmod
+}
+
+#[test] fn fixture() {
+    let mut fixture = common::Fixture::small();
+    fixture.add("/java.logging/META-INF/services/none", vec![b'x'; 1000]);
+    let file = jimage::File::open_native(fixture.write("jmod_fixture.jimage")).unwrap();
+
+    let mut jmod = Vec::new();
+    assert_eq!(file.export_jmod("java.logging", &mut jmod).unwrap(), 3);
+    let entries = unzip(&jmod);
+    let names = entries.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["classes/module-info.class", "classes/META-INF/services/none", "classes/java/util/logging/Logger.class"]);
+    for (name, data) in entries.iter() {
+        assert_eq!(data, &file.find(&format!("java.logging/{}", &name["classes/".len()..])).unwrap().read_to_vec().unwrap());
+    }
+
+    let mut again = Vec::new();
+    file.export_jmod("java.logging", &mut again).unwrap();
+    assert!(jmod == again, "exports should be deterministic");
+
+    let err = file.export_jmod("java.desktop", &mut Vec::new()).err().unwrap();
+    assert!(matches!(err, jimage::Error::NotFound { .. }), "{:?}", err);
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_jdk() {
+    let jdk = common::local_jdk_with_jmods();
+    let jmod_tool = jdk.home().join("bin").join(if cfg!(windows) { "jmod.exe" } else { "jmod" });
+    let path = std::path::PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("jmod_local_jdk.jmod");
+    let _ = std::fs::remove_file(&path);
+    jdk.open().unwrap().export_jmod("java.logging", std::fs::File::create(&path).unwrap()).unwrap();
+
+    let classes = |jmod: &std::path::Path| {
+        let output = Command::new(&jmod_tool).arg("list").arg(jmod).output().unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let mut lines = String::from_utf8(output.stdout).unwrap().lines().filter(|l| l.starts_with("classes/")).map(String::from).collect::<Vec<_>>();
+        lines.sort();
+        lines
+    };
+    assert_eq!(classes(&path), classes(&jdk.home().join("jmods").join("java.logging.jmod")));
+
+    let output = Command::new(&jmod_tool).arg("describe").arg(&path).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("java.logging"));
+}
+
+#[test] fn read_sections() {
+    let module_info = common::module_info("com.example");
+    let jmod = Jmod::from_bytes(jmod_of(&[
+        ("classes/module-info.class",           &module_info),
+        ("classes/com/example/Main.class",      &common::class_bytes("com/example/Main")),
+        ("classes/com/example/data/table.bin",  b"table"),
+        ("conf/example.properties",             b"example=true"),
+        ("lib/libexample.so",                   b"\x7FELF"),
+        ("bin/example",                         b"#!/bin/sh"),
+    ])).unwrap();
+    assert_eq!(jmod.version(), (1, 0));
+    assert_eq!(jmod.module_name(), "com.example");
+    assert_eq!(jmod.packages(), ["com/example", "com/example/data"]);
+    assert_eq!(jmod.entries().len(), 6);
+    assert_eq!(jmod.section(Section::Classes).map(|e| e.name()).collect::<Vec<_>>(), ["module-info.class", "com/example/Main.class", "com/example/data/table.bin"]);
+    assert_eq!(jmod.section(Section::NativeLibs).map(|e| e.name()).collect::<Vec<_>>(), ["libexample.so"]);
+    assert_eq!(jmod.section(Section::LegalNotices).count(), 0);
+
+    let conf = jmod.find(Section::Config, "example.properties").unwrap();
+    assert_eq!((conf.section(), conf.size(), conf.compressed_size()), (Section::Config, 12, 0));
+    assert_eq!(&jmod.read(conf).unwrap()[..], b"example=true");
+    assert!(matches!(jmod.find(Section::Config, "missing.properties").err().unwrap(), jimage::Error::NotFound { .. }));
+    assert!(matches!(jmod.find(Section::Classes, "example.properties").err().unwrap(), jimage::Error::NotFound { .. }));
+
+    let file = jimage::File::from_jmod(jmod);
+    assert_eq!(file.modules().collect::<Vec<_>>(), ["com.example"]);
+    assert_eq!(file.packages_of("com.example").unwrap(), ["com/example", "com/example/data"]);
+    assert_eq!(file.find("/com.example/com/example/data/table.bin").unwrap().read_to_vec().unwrap(), b"table");
+    assert_eq!(file.find_class("com.example.Main").unwrap().read_to_vec().unwrap(), common::class_bytes("com/example/Main"));
+    assert_eq!(file.package_to_module(CStr::from_bytes_with_nul(b"com/example\0").unwrap()).unwrap().to_str().unwrap(), "com.example");
+    assert!(file.find("/com.example/example.properties").is_err(), "conf/ isn't part of the module's resources");
+    assert!(matches!(file.header().err().unwrap(), jimage::Error::Unsupported { .. }));
+    assert!(file.verify().unwrap().is_ok());
+}
+
+#[test] fn read_errors() {
+    let module_info = common::module_info("com.example");
+    let err = |bytes: Vec<u8>| Jmod::from_bytes(bytes).err().unwrap().to_string();
+    assert!(err(b"PK\x03\x04".to_vec()).contains("\"JM\" magic"));
+    assert!(err(b"JM\x02\x00".to_vec()).contains("version 2.0"));
+    assert!(err(b"JM\x01\x00 not a zip".to_vec()).contains("end of central directory"));
+    assert!(err(jmod_of(&[("classes/Main.class", b"")])).contains("module-info.class"));
+    assert!(err(jmod_of(&[("classes/module-info.class", b"\xCA\xFE\xBA\xBE")])).contains("module name"));
+    assert!(err(jmod_of(&[("classes/module-info.class", &module_info), ("unknown/x", b"")])).contains("known section"));
+
+    // Point the only entry's local header (central directory + 42) past the end of the file
+    let mut far = jmod_of(&[("classes/module-info.class", &module_info)]);
+    let central = 4 + u32::from_le_bytes(far[far.len()-6..far.len()-2].try_into().unwrap()) as usize;
+    far[central+42..central+46].copy_from_slice(&0xFFFF_FFF0u32.to_le_bytes());
+    assert!(err(far).contains("local header"));
+
+    let mut corrupt = jmod_of(&[("classes/module-info.class", &module_info), ("conf/x", b"original")]);
+    let at = corrupt.windows(8).position(|w| w == b"original").unwrap();
+    corrupt[at..at+8].copy_from_slice(b"modified");
+    let file = jimage::File::from_jmod(Jmod::from_bytes(corrupt).unwrap());
+    let report = file.verify().unwrap();
+    assert_eq!(report.problems.len(), 1, "{:?}", report);
+    assert!(report.problems[0].to_string().contains("CRC"), "{}", report.problems[0]);
+}
+
+#[test] fn module_info_long_constant() {
+    // Long and Double constants take two constant pool slots - end the pool with one that fits, and one that doesn't
+    let with_long = |count: u16| {
+        let mut class = common::module_info("com.example");
+        let end = class.windows(9).rposition(|w| w == b"\x01\x00\x06Module").unwrap() + 9;
+        class.splice(end..end, [5, 0, 0, 0, 0, 0, 0, 0, 42].iter().copied());
+        class[8..10].copy_from_slice(&count.to_be_bytes());
+        jmod_of(&[("classes/module-info.class", &class)])
+    };
+    assert_eq!(Jmod::from_bytes(with_long(8)).unwrap().module_name(), "com.example");
+    let err = Jmod::from_bytes(with_long(7)).err().unwrap();
+    assert!(matches!(err, jimage::Error::Corrupted { .. }), "{}", err);
+}
+
+#[test] fn export_round_trip() {
+    let mut fixture = common::Fixture::default();
+    fixture.add("/java.logging/module-info.class", common::module_info("java.logging"));
+    fixture.add("/java.logging/java/util/logging/Logger.class", common::class_bytes("java/util/logging/Logger"));
+    fixture.add("/java.logging/META-INF/services/none", vec![b'x'; 1000]);
+    fixture.add("/java.base/java/lang/Object.class", common::class_bytes("java/lang/Object"));
+    let image = jimage::File::open_native(fixture.write("jmod_round_trip.jimage")).unwrap();
+
+    let mut bytes = Vec::new();
+    image.export_jmod("java.logging", &mut bytes).unwrap();
+    let path = std::path::PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("jmod_round_trip.jmod");
+    std::fs::write(&path, bytes).unwrap();
+    let jmod = jimage::File::open_jmod(&path).unwrap();
+    assert_eq!(jmod.path(), Some(path.as_path()));
+
+    let mut visited = Vec::new();
+    jmod.visit(|params| {
+        let resource = params.resource().unwrap();
+        visited.push((params.path().unwrap().to_string(), resource.read_to_vec().unwrap()));
+        jimage::VisitResult::Continue
+    });
+    visited.sort();
+    let mut expected = image.resources_of("java.logging").unwrap().iter().map(|e| (e.path().unwrap().to_string(), e.resource(&image).unwrap().read_to_vec().unwrap())).collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(visited, expected);
+    let mut resources = jmod.resources();
+    assert_eq!(resources.size_hint(), (expected.len(), Some(expected.len())), "enumerated lazily, but exactly sized");
+    resources.next().unwrap();
+    assert_eq!(resources.count(), expected.len() - 1);
+
+    let deflated = jmod.find("/java.logging/META-INF/services/none").unwrap();
+    assert_eq!(deflated.size(), 1000);
+    assert!(deflated.compressed_size().unwrap() > 0 && deflated.compressed_size().unwrap() < 1000);
+    assert_eq!(deflated.offset(), None);
+    assert_eq!(deflated.path().unwrap().to_string(), "/java.logging/META-INF/services/none");
+    assert!(jmod.find("/java.base/java/lang/Object.class").is_err());
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_jdk_jmods() {
+    let jdk = common::local_jdk_with_jmods();
+    let jmod = Jmod::open(jdk.home().join("jmods").join("java.logging.jmod")).unwrap();
+    assert_eq!(jmod.module_name(), "java.logging");
+    assert!(jmod.packages().iter().any(|p| p == "java/util/logging"));
+    let properties = jmod.find(Section::Config, "logging.properties").unwrap();
+    assert_eq!(&jmod.read(properties).unwrap()[..], &std::fs::read(jdk.home().join("conf").join("logging.properties")).unwrap()[..]);
+
+    let image = jdk.open().unwrap();
+    let file = jimage::File::from_jmod(jmod);
+    assert!(file.verify().unwrap().is_ok());
+    let logger = "/java.logging/java/util/logging/Logger.class";
+    assert_eq!(file.find(logger).unwrap().read_to_vec().unwrap(), image.find(logger).unwrap().read_to_vec().unwrap());
+    assert_eq!(file.modules().collect::<Vec<_>>(), ["java.logging"]);
+    assert_eq!(file.packages_of("java.logging").unwrap(), image.packages_of("java.logging").unwrap());
+}
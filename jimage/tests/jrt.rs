@@ -0,0 +1,107 @@
+mod common;
+
+use jimage::{JrtEntry, JrtPath, ResourcePath};
+use std::io::ErrorKind;
+
+fn jrt(s: &str) -> JrtPath { JrtPath::parse(s).unwrap() }
+
+fn list(file: &jimage::File, path: &str) -> Vec<String> {
+    match jrt(path).resolve(file) {
+        Ok(JrtEntry::Directory(children)) => children.iter().map(|c| c.to_string()).collect(),
+        Ok(JrtEntry::Resource(_)) => panic!("{} is a resource, not a directory", path),
+        Err(err) => panic!("{}: {}", path, err),
+    }
+}
+
+#[test] fn parse_and_format() {
+    assert_eq!(jrt("/").as_str(), "/");
+    assert_eq!(jrt(""), JrtPath::root());
+    assert_eq!(jrt("modules").as_str(), "/modules");
+    assert_eq!(jrt("//modules//java.logging/").as_str(), "/modules/java.logging");
+    assert_eq!(jrt("/modules/./java.logging/../java.base/java/lang/Object.class").as_str(), "/modules/java.base/java/lang/Object.class");
+    assert_eq!(jrt("/../..").as_str(), "/");
+    assert_eq!("/packages/java.lang".parse::<JrtPath>().unwrap().to_string(), "/packages/java.lang");
+    assert_eq!(JrtPath::parse("/modules/a\0b").err().unwrap().kind(), ErrorKind::InvalidInput);
+
+    let object = ResourcePath::parse("/java.base/java/lang/Object.class").unwrap();
+    assert_eq!(JrtPath::from(&object).as_str(), "/modules/java.base/java/lang/Object.class");
+    assert_eq!(jrt("/modules/java.base/java/lang/Object.class").resource_path(), Some(object.clone()));
+    assert_eq!(jrt("/packages/java.lang/java.base/java/lang/Object.class").resource_path(), Some(object));
+    for not_a_resource in ["/", "/modules", "/modules/java.base", "/packages/java.lang", "/packages/java.lang/java.base"].iter() {
+        assert_eq!(jrt(not_a_resource).resource_path(), None, "{}", not_a_resource);
+    }
+}
+
+#[test] fn urls() {
+    let url = |s: &str| JrtPath::from_url(s).unwrap().to_string();
+    assert_eq!(url("jrt:/"), "/modules");
+    assert_eq!(url("jrt:/java.base"), "/modules/java.base");
+    assert_eq!(url("jrt:/java.base/java/lang/Object.class"), "/modules/java.base/java/lang/Object.class");
+    assert_eq!(url("JRT:/java.base/java/lang/Object%24Foo.class"), "/modules/java.base/java/lang/Object$Foo.class");
+    assert_eq!(url("jrt:/modules/java.base"), "/modules/modules/java.base", "jrt: URLs are always relative to /modules");
+    for bad in ["", "file:/java.base", "jrt:java.base", "jrt://host/java.base", "jrt:/java.base/../x", "jrt:/java.base/./x", "jrt:/java.base?x", "jrt:/java.base#x", "jrt:/%4", "jrt:/%zz", "jrt:/%FF"].iter() {
+        assert_eq!(JrtPath::from_url(bad).err().unwrap().kind(), ErrorKind::InvalidInput, "{:?}", bad);
+    }
+
+    assert_eq!(jrt("/modules").to_url().unwrap(), "jrt:/");
+    assert_eq!(jrt("/modules/java.logging").to_url().unwrap(), "jrt:/java.logging");
+    assert_eq!(jrt("/modules/java.base/a b$c.class").to_url().unwrap(), "jrt:/java.base/a%20b$c.class");
+    assert_eq!(jrt("/modules/java.base/é").to_url().unwrap(), "jrt:/java.base/%C3%A9");
+    for no_url in ["/", "/packages", "/packages/java.lang/java.base", "/modulesx"].iter() {
+        assert_eq!(jrt(no_url).to_url(), None, "{}", no_url);
+    }
+    for path in ["/modules", "/modules/java.base/a b$c%d.class", "/modules/java.base/é"].iter() {
+        assert_eq!(JrtPath::from_url(&jrt(path).to_url().unwrap()).unwrap(), jrt(path), "round trip {}", path);
+    }
+}
+
+#[test] fn resolve() {
+    let mut fixture = common::Fixture::small();
+    fixture.add("/java.base/META-INF/services/none", b"none".to_vec());
+    let file = jimage::File::open_native(fixture.write("jrt.jimage")).unwrap();
+
+    assert_eq!(list(&file, "/"), ["/modules", "/packages"]);
+    assert_eq!(list(&file, "/modules"), ["/modules/java.base", "/modules/java.logging"]);
+    assert_eq!(list(&file, "/modules/java.base"), ["/modules/java.base/META-INF", "/modules/java.base/java", "/modules/java.base/jdk", "/modules/java.base/module-info.class"]);
+    assert_eq!(list(&file, "/modules/java.base/java/util"), ["/modules/java.base/java/util/Map$Entry.class", "/modules/java.base/java/util/Map.class"]);
+    assert_eq!(list(&file, "/packages"), [
+        "/packages/java", "/packages/java.lang", "/packages/java.util", "/packages/java.util.logging",
+        "/packages/jdk", "/packages/jdk.internal", "/packages/jdk.internal.icu", "/packages/jdk.internal.icu.impl", "/packages/jdk.internal.icu.impl.data", "/packages/jdk.internal.icu.impl.data.icudt64b",
+    ], "parent packages, but no META-INF");
+    assert_eq!(list(&file, "/packages/java"), ["/packages/java/java.base", "/packages/java/java.logging"]);
+    assert_eq!(list(&file, "/packages/java.util"), ["/packages/java.util/java.base", "/packages/java.util/java.logging"]);
+    assert_eq!(list(&file, "/packages/java.lang/java.base"), ["/packages/java.lang/java.base/META-INF", "/packages/java.lang/java.base/java", "/packages/java.lang/java.base/jdk", "/packages/java.lang/java.base/module-info.class"]);
+    assert_eq!(list(&file, "/packages/java.lang/java.base/java"), ["/packages/java.lang/java.base/java/lang", "/packages/java.lang/java.base/java/util"]);
+
+    for path in ["/modules/java.base/java/lang/Object.class", "/packages/java.lang/java.base/java/lang/Object.class", "/modules/java.logging/../java.base/java/lang/Object.class"].iter() {
+        match jrt(path).resolve(&file).ok() {
+            Some(JrtEntry::Resource(r)) => assert_eq!(r.read_to_vec().unwrap(), common::class_bytes("java/lang/Object")),
+            _ => panic!("{} should be a resource", path),
+        }
+        assert_eq!(jrt(path).real_path(&file).unwrap().as_str(), "/modules/java.base/java/lang/Object.class");
+    }
+    assert_eq!(jrt("/packages/java.util.logging/java.logging").real_path(&file).unwrap().as_str(), "/modules/java.logging");
+    assert_eq!(jrt("/packages/java").real_path(&file).unwrap().as_str(), "/packages/java");
+
+    for missing in [
+        "/nope", "/java.base", "/modules/nope", "/modules/java.base/java/lang/Missing.class", "/modules/java.base/java/lan",
+        "/packages/nope", "/packages/META-INF.services", "/packages/java.lang/java.logging", "/packages/java.lang/nope",
+        "/packages/java.lang/java.base/missing", "/packages/META-INF/java.base/META-INF/services/none",
+    ].iter() {
+        assert_eq!(jrt(missing).resolve(&file).err().unwrap().kind(), ErrorKind::NotFound, "{}", missing);
+        assert_eq!(jrt(missing).real_path(&file).err().unwrap().kind(), ErrorKind::NotFound, "{}", missing);
+    }
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_jdk() {
+    let jdk = jimage::Jdk::from_home(common::local_jdk()).unwrap();
+    let file = jdk.open().unwrap();
+    assert!(list(&file, "/modules/java.logging").contains(&"/modules/java.logging/module-info.class".to_string()));
+    assert_eq!(list(&file, "/packages/java.util.logging"), ["/packages/java.util.logging/java.logging"]);
+    let url = "jrt:/java.logging/java/util/logging/Logger.class";
+    match JrtPath::from_url(url).unwrap().resolve(&file).ok() {
+        Some(JrtEntry::Resource(r)) => assert_eq!(r.read_to_vec().unwrap(), file.find("/java.logging/java/util/logging/Logger.class").unwrap().read_to_vec().unwrap()),
+        _ => panic!("{} should be a resource", url),
+    }
+    assert!(list(&file, "/packages/java.lang").contains(&"/packages/java.lang/java.instrument".to_string()), "java.lang.instrument's parent package");
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use std::borrow::Cow;
+use std::ffi::*;
+
+fn cstr(s: &[u8]) -> &CStr { CStr::from_bytes_with_nul(s).unwrap() }
+
+#[test] fn mmap_borrows_uncompressed() {
+    let mut f = common::Fixture::small();
+    let zipped = f.zip(b"compressed text");
+    f.add_compressed("/java.base/zipped.txt", zipped, 15);
+    let file = jimage::File::open_mmap(f.write("mmap_borrows.jimage")).unwrap();
+
+    let object = file.find_resource(cstr(b"java.base\0"), cstr(b"9.0\0"), cstr(b"java/lang/Object.class\0")).unwrap();
+    match object.bytes().unwrap() {
+        Cow::Borrowed(b) => assert_eq!(b, &common::class_bytes("java/lang/Object")[..]),
+        Cow::Owned(_) => panic!("uncompressed resource was copied"),
+    }
+
+    let zipped = file.find_resource(cstr(b"java.base\0"), cstr(b"9.0\0"), cstr(b"zipped.txt\0")).unwrap();
+    match zipped.bytes().unwrap() {
+        Cow::Owned(b) => assert_eq!(b, b"compressed text"),
+        Cow::Borrowed(_) => panic!("compressed resource wasn't decompressed"),
+    }
+}
+
+fn check_matches_native(path: &std::path::Path) {
+    let mapped = jimage::File::open_mmap(path).unwrap();
+    let native = jimage::File::open_native(path).unwrap();
+    let entries = mapped.resources().collect::<Vec<_>>();
+    assert_eq!(entries, native.resources().collect::<Vec<_>>());
+    for entry in entries.iter().step_by(31) {
+        assert_eq!(entry.resource(&mapped).unwrap().bytes().unwrap(), entry.resource(&native).unwrap().bytes().unwrap(), "{:?}", entry);
+    }
+}
+
+#[test] fn mmap_matches_native() {
+    check_matches_native(&common::Fixture::small().write("mmap_matches_native.jimage"));
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_jdk_mmap_matches_native() {
+    check_matches_native(&common::local_jdk().join("lib").join("modules"));
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use std::ffi::*;
+
+#[test] fn fixture_modules() {
+    let file = jimage::File::open_native(common::Fixture::small().write("modules.jimage")).unwrap();
+    assert_eq!(file.modules().collect::<Vec<_>>(), ["java.base", "java.logging"]);
+    assert_eq!(file.packages_of("java.base").unwrap(), ["java/lang", "java/util", "jdk/internal/icu/impl/data/icudt64b"]);
+    assert_eq!(file.packages_of("java.logging").unwrap(), ["java/util/logging"]);
+    assert_eq!(file.packages_of("java.desktop").err().unwrap().kind(), std::io::ErrorKind::NotFound);
+
+    let logging = file.resources_of("java.logging").unwrap();
+    assert_eq!(logging.iter().map(|e| e.path().unwrap().to_string()).collect::<Vec<_>>(), ["/java.logging/module-info.class", "/java.logging/java/util/logging/Logger.class"]);
+    assert_eq!(file.resources_of("java.base").unwrap().len(), 6);
+    assert!(file.resources_of("").is_err());
+
+    let reverse = file.module_to_packages();
+    assert_eq!(reverse.len(), 2);
+    for (module, packages) in reverse.iter() {
+        for package in packages.iter() {
+            assert_eq!(file.package_to_module(&CString::new(package.as_str()).unwrap()).unwrap().to_str().unwrap(), module);
+        }
+    }
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_jdk_modules() {
+    let jdk = common::local_jdk();
+    let modules = jdk.join("lib").join("modules");
+    let c_file = common::load_local_libjimage(&jdk).open(&modules).unwrap();
+    let r_file = jimage::File::open_native(&modules).unwrap();
+    assert_eq!(c_file.modules().collect::<Vec<_>>(), r_file.modules().collect::<Vec<_>>());
+    assert_eq!(c_file.module_to_packages(), r_file.module_to_packages());
+    assert!(r_file.packages_of("java.base").unwrap().iter().any(|p| p == "java/lang"));
+    assert_eq!(r_file.resources_of("java.base").unwrap(), c_file.resources_of("java.base").unwrap());
+    let total : usize = r_file.modules().map(|m| r_file.resources_of(m).unwrap().len()).sum();
+    assert_eq!(total, r_file.resources().count());
+}
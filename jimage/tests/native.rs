@@ -0,0 +1,93 @@
+mod common;
+
+use std::collections::BTreeSet;
+use std::ffi::*;
+
+fn cstr(s: &[u8]) -> &CStr { CStr::from_bytes_with_nul(s).unwrap() }
+
+#[test] fn fixture_visit_and_find() {
+    let path = common::Fixture::small().write("native_fixture_visit_and_find.jimage");
+    let file = jimage::File::open_native(&path).unwrap();
+
+    let mut visited = BTreeSet::new();
+    file.visit(|res|{
+        assert_eq!(res.version().unwrap(), "9");
+        let bytes = {
+            let res = res.resource().unwrap();
+            let mut v = vec![0; res.size() as usize];
+            assert_eq!(res.get(&mut v[..]).unwrap(), res.size());
+            v
+        };
+        if res.extension().unwrap() == "class" { assert_eq!(&bytes[..4], [0xCA, 0xFE, 0xBA, 0xBE]); }
+        visited.insert(format!("/{}/{}/{}.{}", res.module_name().unwrap(), res.package().unwrap(), res.name().unwrap(), res.extension().unwrap()));
+        jimage::VisitResult::Continue
+    });
+    assert_eq!(visited.len(), 8, "{:#?}", visited);
+    assert!(visited.contains("/java.base/java/util/Map$Entry.class"));
+    assert!(visited.contains("/java.logging/java/util/logging/Logger.class"));
+
+    let res = file.find_resource(cstr(b"java.base\0"), cstr(b"9.0\0"), cstr(b"java/lang/Object.class\0")).unwrap();
+    let mut v = vec![0; res.size() as usize];
+    res.get(&mut v[..]).unwrap();
+    assert_eq!(v, common::class_bytes("java/lang/Object"));
+
+    assert_eq!(file.find_resource(cstr(b"java.logging\0"), cstr(b"9.0\0"), cstr(b"java/lang/Object.class\0")).err().unwrap().kind(), std::io::ErrorKind::NotFound);
+    assert_eq!(file.package_to_module(cstr(b"java/util/logging\0")).unwrap().to_bytes(), b"java.logging");
+    assert_eq!(file.package_to_module(cstr(b"java/lang\0")).unwrap().to_bytes(), b"java.base");
+    assert_eq!(file.package_to_module(cstr(b"java/nope\0")).unwrap_err().kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test] fn fixture_visit_cancel() {
+    let path = common::Fixture::small().write("native_fixture_visit_cancel.jimage");
+    let file = jimage::File::open_native(&path).unwrap();
+    let mut n = 0;
+    file.visit(|_|{ n += 1; jimage::VisitResult::Cancel });
+    assert_eq!(n, 1);
+}
+
+#[test] fn bad_magic_and_version() {
+    let mut bytes = common::Fixture::small().to_bytes();
+    bytes[4..8].copy_from_slice(&(2u32 << 16).to_ne_bytes());
+    let path = std::path::PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("native_bad_version.jimage");
+    std::fs::write(&path, &bytes).unwrap();
+    assert!(jimage::File::open_native(&path).err().unwrap().to_string().contains("JIMAGE_BAD_VERSION"));
+
+    bytes[0..4].copy_from_slice(&0xCAFEBABEu32.to_ne_bytes());
+    std::fs::write(&path, &bytes).unwrap();
+    assert!(jimage::File::open_native(&path).err().unwrap().to_string().contains("JIMAGE_BAD_MAGIC"));
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_jdk_matches_libjimage() {
+    let jdk = common::local_jdk();
+    let lib = common::load_local_libjimage(&jdk);
+    let c_file = lib.open(jdk.join("lib").join("modules")).unwrap();
+    let r_file = jimage::File::open_native(jdk.join("lib").join("modules")).unwrap();
+
+    let list = |file: &jimage::File| {
+        let mut v = Vec::new();
+        file.visit(|r|{
+            v.push((r.module_name_cstr().to_owned(), r.version_cstr().to_owned(), r.package_cstr().to_owned(), r.name_cstr().to_owned(), r.extension_cstr().to_owned()));
+            jimage::VisitResult::Continue
+        });
+        v
+    };
+    let c_list = list(&c_file);
+    assert_eq!(c_list, list(&r_file));
+
+    for (module, version, package, name, extension) in c_list.iter().step_by(97) {
+        let package = match package.to_str().unwrap() { "" => String::new(), p => format!("{}/", p) };
+        let extension = match extension.to_str().unwrap() { "" => String::new(), e => format!(".{}", e) };
+        let name = CString::new(format!("{}{}{}", package, name.to_str().unwrap(), extension)).unwrap();
+        let c_res = c_file.find_resource(module, version, &name).unwrap();
+        let r_res = r_file.find_resource(module, version, &name).unwrap();
+        assert_eq!(c_res.size(), r_res.size());
+        let (mut c_buf, mut r_buf) = (vec![0; c_res.size() as usize], vec![0; r_res.size() as usize]);
+        c_res.get(&mut c_buf).unwrap();
+        r_res.get(&mut r_buf).unwrap();
+        assert!(c_buf == r_buf, "{:?}/{:?} differs", module, name);
+    }
+
+    for package in [&b"java/lang\0"[..], b"java/util/logging\0", b"javax/swing\0"].iter() {
+        assert_eq!(c_file.package_to_module(cstr(package)).ok(), r_file.package_to_module(cstr(package)).ok());
+    }
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use jimage::ResourcePath;
+
+#[test] fn parse_and_format() {
+    let p : ResourcePath = "/java.base/java/lang/Object.class".parse().unwrap();
+    assert_eq!(p, ResourcePath::parse("java.base/java/lang/Object.class").unwrap());
+    assert_eq!(p, ResourcePath::new("java.base", "java/lang/Object.class").unwrap());
+    assert_eq!(p.to_string(), "/java.base/java/lang/Object.class");
+    assert_eq!(p.module(),      "java.base");
+    assert_eq!(p.path(),        "java/lang/Object.class");
+    assert_eq!(p.package(),     "java/lang");
+    assert_eq!(p.file_name(),   "Object.class");
+    assert_eq!(p.name(),        "Object");
+    assert_eq!(p.extension(),   "class");
+
+    let info = ResourcePath::parse("/java.base/module-info.class").unwrap();
+    assert_eq!(info.package(), "");
+    assert_eq!(info.name(), "module-info");
+
+    for bad in ["", "/", "java.base", "/java.base", "/java.base/", "//java/lang/Object.class", "/java.base/java/lang/", "/java.base/a\0b"].iter() {
+        let err = ResourcePath::parse(bad).err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput, "{:?}", bad);
+    }
+}
+
+#[test] fn fixture_find() {
+    let file = jimage::File::open_native(common::Fixture::small().write("path_find.jimage")).unwrap();
+    assert_eq!(file.find("/java.base/java/lang/Object.class").unwrap().read_to_vec().unwrap(), common::class_bytes("java/lang/Object"));
+    assert_eq!(file.find("java.base/java/lang/Object.class").unwrap().size(), file.find("/java.base/java/lang/Object.class").unwrap().size());
+    assert!(file.find("/java.base/module-info.class").is_ok());
+    assert_eq!(file.find("/java.logging/java/lang/Object.class").err().unwrap().kind(), std::io::ErrorKind::NotFound);
+    assert_eq!(file.find("Object.class").err().unwrap().kind(), std::io::ErrorKind::InvalidInput);
+
+    for entry in file.resources() {
+        let path = entry.path().unwrap();
+        assert_eq!(file.find_path(&path).unwrap().read_to_vec().unwrap(), entry.resource(&file).unwrap().read_to_vec().unwrap(), "{}", path);
+    }
+    file.visit(|r|{
+        assert_eq!(r.path().unwrap().to_string(), r.to_entry().path().unwrap().to_string());
+        assert_eq!(r.resource().unwrap().size(), file.find_path(&r.path().unwrap()).unwrap().size());
+        jimage::VisitResult::Continue
+    });
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_libjimage_find() {
+    let jdk = common::local_jdk();
+    let file = common::load_local_libjimage(&jdk).open(common::Fixture::small().write("path_find_libjimage.jimage")).unwrap();
+    assert_eq!(file.find("/java.base/java/lang/Object.class").unwrap().read_to_vec().unwrap(), common::class_bytes("java/lang/Object"));
+    assert!(file.find("/java.base/module-info.class").is_ok());
+}
@@ -0,0 +1,32 @@
+#![cfg(feature = "rayon")]
+
+mod common;
+
+use rayon::iter::ParallelIterator;
+
+fn check_par_resources(path: &std::path::Path) {
+    let file = jimage::File::open_mmap(path).unwrap();
+    let mut par = file.par_resources().collect::<Vec<_>>();
+    let mut seq = file.resources().collect::<Vec<_>>();
+    par.sort();
+    seq.sort();
+    assert_eq!(par, seq);
+}
+
+#[test] fn par_resources_match_resources() {
+    check_par_resources(&common::Fixture::small().write("rayon_par_resources.jimage"));
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_jdk_par_resources() {
+    check_par_resources(&common::local_jdk().join("lib").join("modules"));
+}
+
+#[test] fn par_read_resources() {
+    let mut f = common::Fixture::small();
+    let zipped = f.zip(b"compressed text");
+    f.add_compressed("/java.base/zipped.txt", zipped, 15);
+    let file = jimage::File::open_native(f.write("rayon_par_read.jimage")).unwrap();
+    let total = file.par_read_resources().map(|(entry, bytes)| bytes.unwrap_or_else(|err| panic!("{:?}: {}", entry, err)).len()).sum::<usize>();
+    let expected = file.resources().map(|e| e.resource(&file).unwrap().size() as usize).sum::<usize>();
+    assert_eq!(total, expected);
+}
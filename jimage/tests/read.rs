@@ -0,0 +1,62 @@
+mod common;
+
+use std::ffi::*;
+use std::io::{BufRead, Read, Seek, SeekFrom};
+
+fn cstr(s: &[u8]) -> &CStr { CStr::from_bytes_with_nul(s).unwrap() }
+
+fn fixture() -> common::Fixture {
+    let mut f = common::Fixture::small();
+    f.add("/java.base/lines.txt", b"first line\nsecond line\nthird line\n".to_vec());
+    let zipped = f.zip(b"zipped line\nanother\n");
+    f.add_compressed("/java.base/zipped.txt", zipped, 20);
+    f
+}
+
+#[test] fn read_to_vec_and_copy_to() {
+    let file = jimage::File::open_native(fixture().write("read_to_vec.jimage")).unwrap();
+    let res = file.find_resource(cstr(b"java.base\0"), cstr(b"9.0\0"), cstr(b"java/lang/Object.class\0")).unwrap();
+    assert_eq!(res.read_to_vec().unwrap(), common::class_bytes("java/lang/Object"));
+
+    let mut out = Vec::new();
+    assert_eq!(res.copy_to(&mut out).unwrap(), res.size());
+    assert_eq!(out, common::class_bytes("java/lang/Object"));
+
+    let zipped = file.find_resource(cstr(b"java.base\0"), cstr(b"9.0\0"), cstr(b"zipped.txt\0")).unwrap();
+    let mut out = Vec::new();
+    assert_eq!(std::io::copy(&mut zipped.reader().unwrap(), &mut out).unwrap(), 20);
+    assert_eq!(out, b"zipped line\nanother\n");
+}
+
+#[test] fn reader_read_seek_bufread() {
+    let file = jimage::File::open_native(fixture().write("reader.jimage")).unwrap();
+    let res = file.find_resource(cstr(b"java.base\0"), cstr(b"9.0\0"), cstr(b"lines.txt\0")).unwrap();
+    let mut r = res.reader().unwrap();
+    assert_eq!(r.by_ref().lines().next().unwrap().unwrap(), "first line");
+    assert_eq!(r.seek(SeekFrom::End(-11)).unwrap(), res.size() - 11);
+    let mut rest = String::new();
+    r.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, "third line\n");
+    r.seek(SeekFrom::Start(6)).unwrap();
+    let mut word = [0; 4];
+    r.read_exact(&mut word).unwrap();
+    assert_eq!(&word, b"line");
+}
+
+#[test] fn partial_get() {
+    let file = jimage::File::open_native(fixture().write("partial_get.jimage")).unwrap();
+    let res = file.find_resource(cstr(b"java.base\0"), cstr(b"9.0\0"), cstr(b"zipped.txt\0")).unwrap();
+    let mut small = [0; 6];
+    assert_eq!(res.get(&mut small).unwrap(), 6);
+    assert_eq!(&small, b"zipped");
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_libjimage_partial_get() {
+    let jdk = common::local_jdk();
+    let file = common::load_local_libjimage(&jdk).open(fixture().write("partial_get_libjimage.jimage")).unwrap();
+    let res = file.find_resource(cstr(b"java.base\0"), cstr(b"9.0\0"), cstr(b"lines.txt\0")).unwrap();
+    let mut small = [0; 5];
+    assert_eq!(res.get(&mut small).unwrap(), 5);
+    assert_eq!(&small, b"first");
+    assert_eq!(res.read_to_vec().unwrap(), b"first line\nsecond line\nthird line\n");
+}
@@ -0,0 +1,45 @@
+mod common;
+
+fn visited(file: &jimage::File) -> Vec<jimage::ResourceEntry> {
+    let mut v = Vec::new();
+    file.visit(|r|{ v.push(r.to_entry()); jimage::VisitResult::Continue });
+    v
+}
+
+#[test] fn fixture_resources_match_visit() {
+    let file = jimage::File::open_native(common::Fixture::small().write("resources_match_visit.jimage")).unwrap();
+    let entries = file.resources().collect::<Vec<_>>();
+    assert_eq!(entries, visited(&file));
+    assert_eq!(entries.len(), 8);
+
+    let classes = file.resources().filter(|e| e.extension().unwrap() == "class").count();
+    assert_eq!(classes, 7);
+
+    let mut resources = file.resources();
+    let first = resources.next().unwrap();
+    let rest = resources.count();
+    assert_eq!(rest, 7, "{:?} was first", first);
+}
+
+#[test] fn fixture_resources_read() -> std::io::Result<()> {
+    let file = jimage::File::open_native(common::Fixture::small().write("resources_read.jimage"))?;
+    for entry in file.resources().filter(|e| e.module_name_cstr().to_bytes() == b"java.logging") {
+        let res = entry.resource(&file)?;
+        let mut v = vec![0; res.size() as usize];
+        res.get(&mut v)?;
+        assert_eq!(&v[..4], [0xCA, 0xFE, 0xBA, 0xBE], "{:?}", entry);
+    }
+    Ok(())
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_jdk_resources() {
+    let jdk = common::local_jdk();
+    let modules = jdk.join("lib").join("modules");
+    let c_file = common::load_local_libjimage(&jdk).open(&modules).unwrap();
+    let r_file = jimage::File::open_native(&modules).unwrap();
+    let c_entries = c_file.resources().collect::<Vec<_>>();
+    assert_eq!(c_entries, visited(&c_file));
+    assert_eq!(c_entries, r_file.resources().collect::<Vec<_>>());
+    let object = c_entries.iter().find(|e| e.package_cstr().to_bytes() == b"java/lang" && e.name_cstr().to_bytes() == b"Object").unwrap();
+    assert_eq!(object.resource(&c_file).unwrap().size(), object.resource(&r_file).unwrap().size());
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use jimage::{ExplodedModules, ExtractFilter, Jar, ResourcePath, ResourceSource};
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+fn tmp(name: &str) -> PathBuf { PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name) }
+
+fn logging() -> Vec<(&'static str, Vec<u8>)> {
+    vec![
+        ("META-INF/services/none",          vec![b'x'; 1000]),
+        ("java/util/logging/Logger.class",  common::class_bytes("java/util/logging/Logger")),
+        ("module-info.class",               common::module_info("java.logging")),
+    ]
+}
+
+/// Check `source` has exactly the `java.logging` resources of [logging], and nothing that isn't there
+fn check(name: &str, source: &dyn ResourceSource) {
+    let expected = logging();
+    let paths = source.resource_paths().unwrap();
+    assert_eq!(paths.iter().map(|p| p.to_string()).collect::<Vec<_>>(), expected.iter().map(|(p, _)| format!("/java.logging/{}", p)).collect::<Vec<_>>(), "{}", name);
+    assert_eq!(source.module_names().unwrap(), ["java.logging"], "{}", name);
+    for ((_, data), path) in expected.iter().zip(paths.iter()) {
+        assert_eq!(source.resource_size(path).unwrap(), data.len() as u64, "{} {}", name, path);
+        assert_eq!(&source.read_resource(path).unwrap()[..], &data[..], "{} {}", name, path);
+    }
+
+    for missing in ["/java.logging/java/util/logging/Missing.class", "/java.base/module-info.class", "/java.logging/../java.logging/module-info.class"].iter() {
+        let missing = ResourcePath::parse(missing).unwrap();
+        assert_eq!(source.resource_size(&missing).err().unwrap().kind(), ErrorKind::NotFound, "{} {}", name, missing);
+        assert_eq!(source.read_resource(&missing).err().unwrap().kind(), ErrorKind::NotFound, "{} {}", name, missing);
+    }
+}
+
+#[test] fn sources() {
+    let mut fixture = common::Fixture::default();
+    for (path, data) in logging() { fixture.add(&format!("/java.logging/{}", path), data); }
+    let image = jimage::File::open_native(fixture.write("source.jimage")).unwrap();
+    check("image", &image);
+
+    let jmod = tmp("source.jmod");
+    image.export_jmod("java.logging", std::fs::File::create(&jmod).unwrap()).unwrap();
+    check("jmod", &jimage::File::open_jmod(&jmod).unwrap());
+
+    let dir = tmp("source_exploded");
+    let _ = std::fs::remove_dir_all(&dir);
+    image.extract_to(&dir, &ExtractFilter::new()).unwrap();
+    std::fs::write(dir.join("not-a-module.txt"), b"ignored").unwrap();
+    let exploded = ExplodedModules::open(&dir).unwrap();
+    assert_eq!(exploded.root(), dir.as_path());
+    check("exploded", &exploded);
+    assert_eq!(ExplodedModules::open(dir.join("not-a-module.txt")).err().unwrap().kind(), ErrorKind::InvalidInput);
+
+    let mut entries = logging().into_iter().map(|(p, d)| (p.to_string(), d)).collect::<Vec<_>>();
+    entries.push(("java/util/logging/".into(), Vec::new()));
+    let entries = entries.iter().map(|(p, d)| (p.as_str(), &d[..])).collect::<Vec<_>>();
+    let jar = tmp("source-1.0.jar");
+    std::fs::write(&jar, common::zip(&entries)).unwrap();
+    let jar = Jar::open(&jar).unwrap();
+    assert_eq!(jar.module_name(), "java.logging", "named by module-info.class, not the file name");
+    check("jar", &jar);
+
+    let sources : Vec<Box<dyn ResourceSource>> = vec![Box::new(image), Box::new(exploded), Box::new(jar)];
+    let logger = ResourcePath::parse("/java.logging/java/util/logging/Logger.class").unwrap();
+    assert!(sources.iter().all(|s| s.read_resource(&logger).is_ok()));
+}
+
+#[test] fn jar_module_names() {
+    let class = common::class_bytes("org/example/Foo");
+    let path = tmp("foo_bar-baz-1.2.3-SNAPSHOT.jar");
+    std::fs::write(&path, common::zip(&[("org/example/Foo.class", &class)])).unwrap();
+    let jar = Jar::open(&path).unwrap();
+    assert_eq!(jar.module_name(), "foo.bar.baz", "derived from the file name");
+    assert_eq!(jar.path(), Some(path.as_path()));
+    assert_eq!(jar.resource_paths().unwrap(), [ResourcePath::parse("/foo.bar.baz/org/example/Foo.class").unwrap()]);
+
+    let manifest = b"Manifest-Version: 1.0\r\nautomatic-module-name:  org.example \r\n\r\nName: org/example/\r\nAutomatic-Module-Name: ignored\r\n";
+    let path = tmp("manifest-2.jar");
+    std::fs::write(&path, common::zip(&[("META-INF/MANIFEST.MF", manifest), ("org/example/Foo.class", &class)])).unwrap();
+    assert_eq!(Jar::open(&path).unwrap().module_name(), "org.example");
+
+    let bytes = common::zip(&[("org/example/Foo.class", &class)]);
+    let jar = Jar::from_bytes(bytes, "org.example.fallback").unwrap();
+    assert_eq!(jar.module_name(), "org.example.fallback");
+    assert_eq!(jar.path(), None);
+
+    let path = tmp("-1.0.jar");
+    std::fs::write(&path, common::zip(&[("org/example/Foo.class", &class)])).unwrap();
+    assert_eq!(Jar::open(&path).err().unwrap().kind(), ErrorKind::InvalidInput);
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_jdk() {
+    let jdk = common::local_jdk_with_jmods();
+    let image = jdk.open().unwrap();
+    let jmod = jimage::File::open_jmod(jdk.home().join("jmods").join("java.logging.jmod")).unwrap();
+    let paths = jmod.resource_paths().unwrap();
+    assert!(paths.len() > 10);
+    for path in paths.iter() {
+        assert_eq!(jmod.read_resource(path).unwrap(), image.read_resource(path).unwrap(), "{}", path);
+    }
+}
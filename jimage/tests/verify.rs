@@ -0,0 +1,58 @@
+mod common;
+
+use jimage::{Header, Problem};
+use std::path::PathBuf;
+
+fn write(name: &str, bytes: &[u8]) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, bytes).unwrap();
+    path
+}
+
+#[test] fn fixture_verifies() {
+    let mut f = common::Fixture::small();
+    let zipped = f.zip(b"compressed text");
+    f.add_compressed("/java.base/zipped.txt", zipped, 15);
+    let file = jimage::File::open_native(f.write("verify_ok.jimage")).unwrap();
+    let report = file.verify().unwrap();
+    assert!(report.is_ok(), "{:?}", report.problems);
+    assert_eq!(report.locations, file.header().unwrap().table_length);
+    assert!(report.resources as usize > file.resources().count(), "directories should be checked too");
+}
+
+#[test] fn bad_decompressed_size() {
+    let mut f = common::Fixture::small();
+    let zipped = f.zip(b"compressed text");
+    f.add_compressed("/java.base/zipped.txt", zipped, 20);
+    let report = jimage::File::open_native(f.write("verify_bad_size.jimage")).unwrap().verify().unwrap();
+    match &report.problems[..] {
+        [Problem::Decompression { resource, message, .. }] => { assert_eq!(resource, "/java.base/zipped.txt"); assert!(message.contains("20"), "{}", message); },
+        other => panic!("expected a single Decompression problem, got {:?}", other),
+    }
+}
+
+#[test] fn truncated_content() {
+    let bytes = common::Fixture::small().to_bytes();
+    let report = jimage::File::open_native(write("verify_truncated.jimage", &bytes[..bytes.len()-1])).unwrap().verify().unwrap();
+    assert!(!report.is_ok());
+    assert!(report.problems.iter().all(|p| matches!(p, Problem::ContentOutOfBounds { .. })), "{:?}", report.problems);
+}
+
+#[test] fn broken_redirects() {
+    let mut bytes = common::Fixture::small().to_bytes();
+    let header = Header::read(&bytes).unwrap();
+    for b in &mut bytes[header.redirect().start as usize .. header.redirect().end as usize] { *b = 0; }
+    let file = jimage::File::open_native(write("verify_redirects.jimage", &bytes)).unwrap();
+    let report = file.verify().unwrap();
+    assert_eq!(report.problems.len() as u32, report.resources);
+    assert!(report.problems.iter().all(|p| matches!(p, Problem::HashMismatch { found: None, .. })), "{:?}", report.problems);
+    assert!(report.problems[0].to_string().contains("doesn't hash"), "{}", report.problems[0]);
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_jdk_verifies() {
+    let jdk = common::local_jdk();
+    let file = common::load_local_libjimage(&jdk).open(jdk.join("lib").join("modules")).unwrap();
+    let report = file.verify().unwrap();
+    assert!(report.is_ok(), "{:?}", report.problems);
+    assert_eq!(report.locations, file.header().unwrap().table_length);
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use jimage::write::ImageBuilder;
+use jimage::{ByteOrder, Error};
+use std::ffi::CStr;
+
+fn cstr(s: &[u8]) -> &CStr { CStr::from_bytes_with_nul(s).unwrap() }
+
+#[test] fn invalid_input() {
+    let mut b = ImageBuilder::new();
+    b.add("java.base", "java/lang/Object.class", common::class_bytes("java/lang/Object")).unwrap();
+    for (module, path) in [("java.base", "java/lang/Object.class"), ("", "a.txt"), ("java/base", "a.txt"), ("packages", "a.txt"), ("modules", "a.txt"), ("java.base", ""), ("java.base", "/a.txt"), ("java.base", "a/"), ("java.base", "a//b.txt"), ("java.base", "a\0.txt")].iter() {
+        let err = b.add(module, path, Vec::new()).err().unwrap_or_else(|| panic!("({:?}, {:?}) should've been rejected", module, path));
+        assert!(matches!(err, Error::InvalidInput { .. }), "{:?}", err);
+    }
+}
+
+#[test] fn round_trip() {
+    for &byte_order in [ByteOrder::LittleEndian, ByteOrder::BigEndian].iter() {
+        let mut b = ImageBuilder::new();
+        b.set_byte_order(byte_order); // before zip(), which writes a resource header
+        for i in 0 .. 2000 {
+            b.add(&format!("mod{}", i % 7), &format!("pkg{}/sub/Resource{}.txt", i % 13, i), format!("content of {}", i)).unwrap();
+        }
+        let zipped = b.zip(b"compressed content");
+        b.add_compressed("mod0", "zipped.txt", zipped, 18).unwrap();
+        b.add("mod0", "no-extension", b"".to_vec()).unwrap();
+
+        let file = jimage::File::from_bytes(b.to_bytes().unwrap()).unwrap();
+        assert_eq!(file.header().unwrap().byte_order, byte_order);
+        assert!(file.verify().unwrap().is_ok(), "{:?}", file.verify().unwrap().problems);
+        assert_eq!(file.resources().count(), 2002);
+        for i in (0 .. 2000).step_by(97) {
+            let path = format!("/mod{}/pkg{}/sub/Resource{}.txt", i % 7, i % 13, i);
+            assert_eq!(file.find(&path).unwrap().read_to_vec().unwrap(), format!("content of {}", i).into_bytes());
+        }
+        assert_eq!(file.find("/mod0/zipped.txt").unwrap().read_to_vec().unwrap(), b"compressed content");
+        assert_eq!(file.find("/mod0/no-extension").unwrap().size(), 0);
+        assert_eq!(file.modules().count(), 7);
+        assert_eq!(file.packages_of("mod3").unwrap().len(), 13);
+        assert_eq!(file.package_to_module(cstr(b"pkg5/sub\0")).unwrap(), cstr(b"mod0\0"));
+    }
+}
+
+#[test] #[ignore = "needs a local JDK, run with --ignored"] fn local_libjimage_opens_written_image() {
+    let jdk = common::local_jdk();
+    let lib = common::load_local_libjimage(&jdk);
+    let jdk = jimage::File::open_mmap(jdk.join("lib").join("modules")).unwrap();
+
+    // Trim the JDK's own image down to a single module, then compare libjimage's view of it against ours
+    let mut b = ImageBuilder::new();
+    for entry in jdk.resources_of("java.logging").unwrap() {
+        let path = entry.path().unwrap().to_string();
+        let bytes = jdk.find(&path).unwrap().read_to_vec().unwrap();
+        b.add("java.logging", &path["/java.logging/".len()..], bytes).unwrap();
+    }
+    let path = std::path::PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("write_java_logging.jimage");
+    b.write(&path).unwrap();
+
+    let file = lib.open(&path).unwrap();
+    for entry in jdk.resources_of("java.logging").unwrap() {
+        let path = entry.path().unwrap().to_string();
+        assert_eq!(file.find(&path).unwrap().read_to_vec().unwrap(), jdk.find(&path).unwrap().read_to_vec().unwrap(), "{}", path);
+    }
+    assert_eq!(file.package_to_module(cstr(b"java/util/logging\0")).unwrap(), cstr(b"java.logging\0"));
+}
@@ -1,6 +1,14 @@
 @pushd "%~dp0.."
 cargo build --all || goto :err
+@set "CARGO_RESOLVER_INCOMPATIBLE_RUST_VERSIONS=fallback"
+cargo generate-lockfile || goto :err
+cargo +1.74.0 build --all --features jimage/rayon || goto :err
+cargo +1.74.0 test  --all --features jimage/rayon || goto :err
+@set "CARGO_RESOLVER_INCOMPATIBLE_RUST_VERSIONS="
+cargo update || goto :err
 cargo test  --all || goto :err
+cargo test  --all --features jimage/rayon || goto :err
+@where java >NUL 2>NUL && (cargo test --all -- --ignored || goto :err)
 cargo build --all --target=i686-pc-windows-msvc || goto :err
 cargo test  --all --target=i686-pc-windows-msvc || goto :err
 cargo +nightly bench --all || goto :err
@@ -6,3 +6,11 @@ function print_run {
 
 print_run cargo build --all || exit 1
 print_run cargo test  --all || exit 1
+print_run cargo test  --all --features jimage/rayon || exit 1
+
+# Tests comparing against a real JDK are #[ignore]d by default
+if command -v java >/dev/null; then
+    print_run cargo test --all -- --ignored || exit 1
+else
+    echo "No java on PATH, not running tests that need a local JDK"
+fi